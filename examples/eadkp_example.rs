@@ -7,19 +7,13 @@
 extern crate eadkp;
 
 // Additional imports needed for the application logic
-use postcard::to_slice;
 use serde::{Serialize, Deserialize};
-use heapless::Vec;
 use eadkp::storage;
 
 // Configure EADK application with metadata
 // This macro will generate: HEAP, alloc imports, format!, String, panic handler, and EADK metadata
 eadk_setup!(name = "Eadkp example");
 
-// Additional imports after macro expansion (these are now available)
-#[cfg(target_os = "none")]
-use alloc::string::ToString;
-
 #[allow(dead_code)]
 #[derive(Clone, Copy)]
 pub struct FormatDurationOptions {
@@ -196,152 +190,28 @@ fn main() -> isize {
         )
     }
 
-    // Convertir des bytes en string hexadécimale
-    #[allow(dead_code)]
-    fn bytes_to_hex_string(bytes: &[u8]) -> String {
-        let mut hex_string = String::new();
-        for byte in bytes {
-            hex_string.push_str(&format!("{:02x}", byte));
-        }
-        hex_string
-    }
-
-    // Convertir string hexadécimale en bytes
-    #[allow(dead_code)]
-    fn hex_string_to_bytes(hex_str: &str) -> Result<Vec<u8, 256>, String> {
-        if hex_str.len() % 2 != 0 {
-            return Err("Longueur impaire".to_string());
-        }
-
-        let mut bytes: Vec<u8, 256> = Vec::new();
-
-        for chunk in hex_str.as_bytes().chunks(2) {
-            let hex_byte_str = core::str::from_utf8(chunk).map_err(|_| "Échec de conversion UTF-8".to_string())?;
-            let byte_val = u8::from_str_radix(hex_byte_str, 16).map_err(|_| "Caractère hexadécimal invalide".to_string())?;
-            bytes.push(byte_val).map_err(|_| "Buffer plein".to_string())?;
-        }
-
-        Ok(bytes)
-    }
-
-    // Struct → Bytes
-    #[allow(dead_code)]
-    fn struct_to_bytes(stats: &GameData) -> Option<Vec<u8, 256>> {
-        let mut buffer = [0u8; 256];
-        if let Ok(used) = to_slice(stats, &mut buffer) {
-            let mut result: Vec<u8, 256> = Vec::new();
-            for byte in used {
-                if result.push(*byte).is_err() {
-                    return None;
-                }
-            }
-            Some(result)
-        } else {
-            None
-        }
-    }
-
-    // Bytes → Struct
-    #[allow(dead_code)]
-    fn bytes_to_struct(bytes: &[u8]) -> Option<GameData> {
-        if let Ok(stats) = postcard::from_bytes::<GameData>(bytes) {
-            Some(stats)
-        } else {
-            None
-        }
-    }
+    const SAVE_FILE: &str = "bounce_data.sav";
+    const SAVE_VERSION: u16 = 1;
 
-    #[cfg(target_os = "none")]
     fn save_data(data: &GameData) -> bool {
-
-        // Convertir data en bytes et sauvegarder
-        if let Some(bytes) = struct_to_bytes(&data) {
-
-            // Convertir les bytes en hexadécimal au format UTF-8
-            let hex_string = bytes_to_hex_string(&bytes);
-
-            let filename = "bounce_data.py";
-
-            // Si le fichier existe déja, le supprimer
-            unsafe {
-                if storage::file_exists(filename) {
-                    let _ = storage::file_erase(filename);
-                }
-
-                // Écrire dans le fichier de sauvegarde                            Le premier caractère sera effacé pour une raison inconnue, il faut donc ajouter un caractère inutile au début, comme un espace.
-                let content = format!(" # This file contains the save data for the Bounce app.\n# Do not edit this file manually, as it may corrupt your save data.\n\n{}", hex_string);
-                return storage::file_write_string(filename, content.as_str()).is_ok();
-            }
-        }
-        false
-    }
-
-    #[cfg(not(target_os = "none"))]
-    fn save_data(_data: &GameData) -> bool {
-        false
+        // Sérialiser avec postcard et remplacer la sauvegarde existante sans jamais la perdre
+        storage::save_versioned(SAVE_FILE, SAVE_VERSION, data).is_ok()
     }
 
-    #[cfg(target_os = "none")]
     fn load_data() -> Option<GameData> {
-        use alloc::string::ToString;
-        
-        let filename = "bounce_data.py";
+        match storage::load_versioned(SAVE_FILE, SAVE_VERSION) {
+            Ok(data) => Some(data),
 
-        // Si le fichier n'existe pas, retourner des données par défaut
-        if storage::file_exists(filename) == false {
-            return Some(GameData {
+            // Si le fichier n'existe pas, retourner des données par défaut
+            Err(storage::StorageError::FileNotFound) => Some(GameData {
                 bounces: 15,
                 total_time: 15,
                 max_time: 15
-            });
-        }
+            }),
 
-        // Charger les données depuis le fichier
-        let file_content = unsafe { storage::file_read_string(filename).ok()};
-        
-        // Convertir le pointer en String
-        let clean_data = file_content.unwrap_or("").to_string();
-        
-        // 3. Extraire toutes les lignes qui ne sont pas des commentaires
-        let hex_string: String = clean_data.lines()
-            .map(|line| line.trim()) // Supprimer espaces et retours chariot
-            .filter(|line| !line.is_empty() && !line.starts_with('#'))
-            .collect::<Vec<&str, 256>>()
-            .join("");
-        
-        // 4. Nettoyer la chaîne hex des caractères non-hex
-        let clean_hex: String = hex_string.chars()
-            .filter(|c| c.is_ascii_hexdigit())
-            .collect::<String>();
-        
-        // Diagnostic: vérifier si on a trouvé des données hex
-        if clean_hex.is_empty() {
-            panic!("Aucune donnée hex trouvée dans le fichier. Contenu nettoyé: '{}', Contenu brut: '{}'", 
-                    &hex_string[..core::cmp::min(100, hex_string.len())],
-                    &clean_data[..core::cmp::min(100, clean_data.len())]);
+            // Sauvegarde corrompue ou d'une autre version
+            Err(_) => None,
         }
-        
-        // Convertir la chaîne hexadécimale en bytes
-        let bytes = match hex_string_to_bytes(&clean_hex) {
-            Ok(b) => b,
-            Err(e) => panic!("Erreur de conversion hexadecimale: \n{}\n{}", clean_hex, e),
-        };
-            
-        // Convertir les bytes en struct
-        let loaded_data = match bytes_to_struct(&bytes) {
-            Some(data) => data,
-            None => panic!("Erreur de désérialisation postcard. Taille bytes: {}, Premiers bytes: {:?}", 
-                            bytes.len(), 
-                            &bytes[..core::cmp::min(16, bytes.len())])
-        };
-                
-        // Retourner les données chargées
-        Some(loaded_data)
-    }
-
-    #[cfg(not(target_os = "none"))]
-    fn load_data() -> Option<GameData> {
-        None
     }
 
 
@@ -394,7 +264,7 @@ fn main() -> isize {
     let mut on_the_last_frame_drawn = false;
 
     // Préparer l'image bread une seule fois
-    let bread_image = eadkp::Image::from_raw(
+    let bread_image = eadkp::ImageRef::from_raw(
         include_image!("bread.png")
     ).expect("Failed to load bread image");
 
@@ -470,44 +340,37 @@ fn main() -> isize {
         };
 
         const HEIGHT: u16 = eadkp::SMALL_FONT.height;
-        const WIDTH: u16 = eadkp::SMALL_FONT.width;
+        const RIGHT_COLUMN_X: u16 = eadkp::SCREEN_RECT.width / 2;
+        let text_colors = eadkp::display::TextColors { text: TEXT_COLOR, background: BACKGROUND_COLOR };
         eadkp::display::draw_string(&format!("Bounces: {}", format_number(bounces as i128)), eadkp::Point { x: 5, y: 5 + HEIGHT * 0}, false, TEXT_COLOR, BACKGROUND_COLOR);
         eadkp::display::draw_string(&format!("FPS: {}", fps_display), eadkp::Point { x: 5, y: 5 + HEIGHT * 1 }, false, TEXT_COLOR, BACKGROUND_COLOR);
         eadkp::display::draw_string(&format!("Time: {}", format_duration_with_options(actual_time, &time_format_options)), eadkp::Point { x: 5, y: 5 + HEIGHT * 2 }, false, TEXT_COLOR, BACKGROUND_COLOR);
 
         let total_time_str = format!("Total: {}", format_duration_with_options(total_time, &time_format_options));
-        eadkp::display::draw_string(&total_time_str, eadkp::Point { x: eadkp::SCREEN_RECT.width - WIDTH * total_time_str.len() as u16 - 5, y: 5 + HEIGHT * 0 }, false, TEXT_COLOR, BACKGROUND_COLOR);
+        eadkp::display::draw_text_box(eadkp::Rect { x: RIGHT_COLUMN_X, y: 5 + HEIGHT * 0, width: eadkp::SCREEN_RECT.width - RIGHT_COLUMN_X - 5, height: HEIGHT }, &total_time_str, eadkp::display::Align::TOP_RIGHT, eadkp::SMALL_FONT, text_colors);
 
         let max_time_str = format!("Best: {}", format_duration_with_options(max_time, &time_format_options));
-        eadkp::display::draw_string(&max_time_str, eadkp::Point { x: eadkp::SCREEN_RECT.width - WIDTH * max_time_str.len() as u16 - 5, y: 5 + HEIGHT * 1 }, false, TEXT_COLOR, BACKGROUND_COLOR);
+        eadkp::display::draw_text_box(eadkp::Rect { x: RIGHT_COLUMN_X, y: 5 + HEIGHT * 1, width: eadkp::SCREEN_RECT.width - RIGHT_COLUMN_X - 5, height: HEIGHT }, &max_time_str, eadkp::display::Align::TOP_RIGHT, eadkp::SMALL_FONT, text_colors);
 
         let battery_str = format!("Battery: {}", eadkp::battery::level().to_str().to_uppercase());
-        eadkp::display::draw_string(&battery_str, eadkp::Point { x: eadkp::SCREEN_RECT.width - WIDTH * battery_str.len() as u16 - 5, y: 5 + HEIGHT * 2 }, false, TEXT_COLOR, BACKGROUND_COLOR);
+        eadkp::display::draw_text_box(eadkp::Rect { x: RIGHT_COLUMN_X, y: 5 + HEIGHT * 2, width: eadkp::SCREEN_RECT.width - RIGHT_COLUMN_X - 5, height: HEIGHT }, &battery_str, eadkp::display::Align::TOP_RIGHT, eadkp::SMALL_FONT, text_colors);
 
 
         // Gérer l'affichage du popup
         let popup_displayed = !info.is_empty() && eadkp::timing::millis() < info_time;
 
-        const POPUP_DISPLAY_AXE_X:u16 = eadkp::SCREEN_RECT.width / 2;
         const POPUP_DISPLAY_AXE_Y:u16 = eadkp::SCREEN_RECT.height - eadkp::LARGE_FONT.height - 5;
 
         // Afficher le message popup s'il est encore valide
         if popup_displayed {
 
-            const MAX_CHARACTERS: usize = (eadkp::SCREEN_RECT.width / eadkp::SMALL_FONT.width) as usize; // Nombre maximum de caractères affichables en une ligne
-
-            let truncated_info = info.clone(); // Cloner la chaîne pour éviter de modifier l'original
-            let must_be_cuted = truncated_info.len() > MAX_CHARACTERS; // Vérifier si la chaîne doit être coupée
-
-            let cuted_info = &truncated_info[..if must_be_cuted { MAX_CHARACTERS - 3 } else { truncated_info.len() }]; // Couper la chaîne si elle est trop longue
-            let display_info = if must_be_cuted { format!("{}...", cuted_info) } else { String::from(cuted_info) };// Chaîne de texte finale à afficher
-
-            eadkp::display::draw_string(
-                &display_info,
-                eadkp::Point { x: POPUP_DISPLAY_AXE_X - ((display_info.len() as u16 * WIDTH) / 2), y: POPUP_DISPLAY_AXE_Y },
-                true,
-                TEXT_COLOR,
-                BACKGROUND_COLOR
+            // Centrer le message, tronqué avec "..." s'il dépasse la largeur de l'écran
+            eadkp::display::draw_text_box(
+                eadkp::Rect { x: 0, y: POPUP_DISPLAY_AXE_Y, width: eadkp::SCREEN_RECT.width, height: eadkp::LARGE_FONT.height },
+                &info,
+                eadkp::display::Align::CENTER,
+                eadkp::LARGE_FONT,
+                text_colors,
             );
             
             on_the_last_frame_drawn = true;
@@ -524,10 +387,7 @@ fn main() -> isize {
         // Afficher l'image bread.png a l'écran
         // println!("Drawing bread image at position ({}, {})", eadkp::SCREEN_RECT.width - bread_image.width, eadkp::SCREEN_RECT.height - bread_image.height);
         // eadkp::display::push_rect_uniform(eadkp::Rect { x: eadkp::SCREEN_RECT.width - bread_image.width, y: eadkp::SCREEN_RECT.height - bread_image.height, width: bread_image.width, height: bread_image.height }, eadkp::Color::from_888(0, 0, 0));
-        eadkp::display::push_rect(
-            bread_image.for_coordinates(eadkp::SCREEN_RECT.width - bread_image.width, eadkp::SCREEN_RECT.height - bread_image.height),
-            bread_image.get_pixels(),
-        );
+        bread_image.draw(eadkp::Point { x: eadkp::SCREEN_RECT.width - bread_image.width, y: eadkp::SCREEN_RECT.height - bread_image.height });
 
         if just.key_down(eadkp::input::Key::Back) {
 
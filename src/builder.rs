@@ -11,6 +11,14 @@ const ASSETS_DIR: &str = "assets/";
 const POSSIBLE_C_DIRS: [&str; 4] = ["src/libs", "src/lib", "src/c", "src/cpp"];
 const KEYBOARD_MAPPING_FILE: &str = "epsilon_simulator/ion/src/simulator/shared/keyboard.cpp";
 
+const FONTS_DIR: &str = "fonts";
+/// Révision du format EIF2 écrite par le builder
+const EIF2_VERSION: u8 = 1;
+/// Variable d'environnement choisissant le format des images converties (`eif2` par défaut, ou `eif1`)
+const IMAGE_FORMAT_VAR: &str = "EADKP_IMAGE_FORMAT";
+/// Espacement (en pixels) ajouté après chaque glyphe d'une police
+const FONT_GLYPH_SPACING: u32 = 1;
+
 const ICON_WIDTH: u32 = 55;
 const ICON_HEIGHT: u32 = 56;
 
@@ -29,36 +37,251 @@ macro_rules! cargo_changed {
     };
 }
 
+/// Compresse une suite d'unités (2 octets pour le RGB565, 1 pour les palettes) en RLE, type PackBits.
+///
+/// Octet de contrôle `c` : si `c < 128`, `c + 1` unités brutes suivent, sinon l'unité suivante est répétée `c - 126` fois.
+fn eif2_rle_encode(data: &[u8], unit: usize) -> Vec<u8> {
+    let units: Vec<&[u8]> = data.chunks(unit).collect();
+    let mut encoded: Vec<u8> = Vec::new();
+    let mut literals: Vec<&[u8]> = Vec::new();
+
+    fn flush(encoded: &mut Vec<u8>, literals: &mut Vec<&[u8]>) {
+        if !literals.is_empty() {
+            encoded.push((literals.len() - 1) as u8);
+            literals.drain(..).for_each(|unit| encoded.extend(unit));
+        }
+    }
+
+    let mut i = 0;
+    while i < units.len() {
+        // Longueur de la répétition commençant à i (129 unités au maximum)
+        let mut run = 1;
+        while i + run < units.len() && units[i + run] == units[i] && run < 129 {
+            run += 1;
+        }
+
+        if run >= 2 {
+            flush(&mut encoded, &mut literals);
+            encoded.push((run + 126) as u8);
+            encoded.extend(units[i]);
+            i += run;
+        } else {
+            literals.push(units[i]);
+            if literals.len() == 128 {
+                flush(&mut encoded, &mut literals);
+            }
+            i += 1;
+        }
+    }
+    flush(&mut encoded, &mut literals);
+
+    encoded
+}
+
+/// Compresse des octets en LZSS (fenêtre de 4096 octets, références de 3 à 18 octets).
+///
+/// Un octet de drapeaux précède chaque groupe de 8 éléments (bit de poids faible en premier) :
+/// `1` = octet brut, `0` = référence sur 2 octets (distance - 1 sur 12 bits, longueur - 3 sur 4 bits).
+fn eif2_lz_encode(data: &[u8]) -> Vec<u8> {
+    const WINDOW: usize = 4096;
+    const MIN_MATCH: usize = 3;
+    const MAX_MATCH: usize = 18;
+    const MAX_CANDIDATES: usize = 64;
+
+    let mut encoded: Vec<u8> = Vec::new();
+    // Positions précédentes de chaque suite de 3 octets
+    let mut chains: std::collections::HashMap<[u8; 3], Vec<usize>> = std::collections::HashMap::new();
+    let insert = |chains: &mut std::collections::HashMap<[u8; 3], Vec<usize>>, position: usize| {
+        if position + MIN_MATCH <= data.len() {
+            chains.entry([data[position], data[position + 1], data[position + 2]]).or_default().push(position);
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let flags_position = encoded.len();
+        encoded.push(0);
+
+        for bit in 0..8 {
+            if i >= data.len() {
+                break;
+            }
+
+            // Chercher la plus longue correspondance parmi les positions récentes
+            let (mut best_length, mut best_distance) = (0, 0);
+            if i + MIN_MATCH <= data.len() {
+                if let Some(candidates) = chains.get(&[data[i], data[i + 1], data[i + 2]]) {
+                    for &candidate in candidates.iter().rev().take(MAX_CANDIDATES) {
+                        if i - candidate > WINDOW {
+                            break;
+                        }
+                        let length = (0..MAX_MATCH.min(data.len() - i))
+                            .take_while(|&k| data[candidate + k] == data[i + k])
+                            .count();
+                        if length > best_length {
+                            (best_length, best_distance) = (length, i - candidate);
+                        }
+                    }
+                }
+            }
+
+            if best_length >= MIN_MATCH {
+                encoded.push(((best_distance - 1) >> 4) as u8);
+                encoded.push((((best_distance - 1) & 0x0F) << 4) as u8 | (best_length - MIN_MATCH) as u8);
+                for position in i..i + best_length {
+                    insert(&mut chains, position);
+                }
+                i += best_length;
+            } else {
+                encoded[flags_position] |= 1 << bit;
+                encoded.push(data[i]);
+                insert(&mut chains, i);
+                i += 1;
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Convertit une image PNG au format EIF2 en choisissant l'encodage le plus petit
+/// (RGB565 direct ou palette de 1, 2, 4 ou 8 bits, sans compression, RLE ou LZ).
+///
+/// Les pixels complètement transparents (alpha = 0) sont marqués par une couleur
+/// inutilisée de l'image (color key), ou par un masque alpha si toutes les couleurs sont prises.
+///
+/// Avec `EADKP_IMAGE_FORMAT=eif1`, l'image est écrite au format EIF1 (RGB565 brut, pixels transparents blancs)
+/// pour les applications qui dépendent encore de ce format.
 fn convert_image(file_path: &std::path::Path) {
     let img = ImageReader::open(file_path)
         .unwrap()
         .decode()
         .unwrap();
 
-    let mut converted_pixels: Vec<u8> = Vec::new();
+    assert!(img.width() <= u16::MAX as u32 && img.height() <= u16::MAX as u32, "Image {} is too large", file_path.display());
 
-    // Ajouter l'en-tête EIF1 au DÉBUT
-    converted_pixels.extend(utils::EIF1_MAGIC_NUMBER.to_le_bytes()); // Magic number (4 bytes)
-    converted_pixels.extend((img.width() as u16).to_le_bytes());         // Width (2 bytes)
-    converted_pixels.extend((img.height() as u16).to_le_bytes());        // Height (2 bytes)
+    // Convertir les pixels en RGB565 et repérer les pixels transparents
+    let mut colors: Vec<u16> = Vec::new();
+    let mut transparent: Vec<bool> = Vec::new();
+    for pix in img.pixels() {
+        let rgb565 = ((pix.2.0[0] as u16 & 0b11111000) << 8) // Mettre les bits rouges a 15-11
+                        | ((pix.2.0[1] as u16 & 0b11111100) << 3) // Mettre les bits verts a 10-5
+                        | (pix.2.0[2] as u16 >> 3);               // Mettre les bits bleus a 4-0
+        colors.push(rgb565);
+        transparent.push(pix.2.0[3] == 0);
+    }
 
-    // Ensuite ajouter les pixels RGB565
-    for pix in img.pixels() { // Convert to RGB565
+    // Format EIF1 demandé : pixels RGB565 bruts, les pixels transparents deviennent blancs
+    if std::env::var(IMAGE_FORMAT_VAR).is_ok_and(|format| format.eq_ignore_ascii_case("eif1")) {
+        let mut converted_pixels: Vec<u8> = Vec::new();
+        converted_pixels.extend(utils::EIF1_MAGIC_NUMBER.to_le_bytes());     // Magic number (4 bytes)
+        converted_pixels.extend((img.width() as u16).to_le_bytes());         // Width (2 bytes)
+        converted_pixels.extend((img.height() as u16).to_le_bytes());        // Height (2 bytes)
+        colors
+            .iter()
+            .zip(&transparent)
+            .for_each(|(&color, &t)| converted_pixels.extend(if t { 0xFFFF } else { color }.to_le_bytes()));
+        write_image(file_path, &converted_pixels);
+        return;
+    }
 
-        // TODO: Utiliser une méthode configuration pour indiquer la couleur de transparence
-        // Si le pixel est complètement transparent (ex: background PNG)
-        if pix.2.0[3] == 0 {
-            // Pixel transparent, le convertir en blanc
-            converted_pixels.extend(0xFFFFu16.to_le_bytes()); // Blanc en RGB565
-            continue;
+    // Choisir une couleur inutilisée comme color key (magenta de préférence)
+    let has_transparency = transparent.iter().any(|&t| t);
+    let used: std::collections::HashSet<u16> = colors.iter().zip(&transparent).filter(|(_, &t)| !t).map(|(&c, _)| c).collect();
+    let color_key = if has_transparency {
+        std::iter::once(0xF81Fu16).chain(0..=u16::MAX).find(|color| !used.contains(color))
+    } else {
+        None
+    };
+
+    // Sans color key possible, les pixels transparents sont blancs et un masque alpha est ajouté
+    let mask: Option<Vec<u8>> = (has_transparency && color_key.is_none()).then(|| {
+        let mut mask = vec![0u8; transparent.len().div_ceil(8)];
+        for (index, _) in transparent.iter().enumerate().filter(|(_, &t)| !t) {
+            mask[index / 8] |= 0x80 >> (index % 8);
         }
+        mask
+    });
+    for (color, _) in colors.iter_mut().zip(&transparent).filter(|(_, &t)| t) {
+        *color = color_key.unwrap_or(0xFFFF);
+    }
 
-        let rgb565 = ((pix.2.0[0] as u16 & 0b11111000) << 8) // Mettre les bits rouges a 15-11
-                        | ((pix.2.0[1] as u16 & 0b11111100) << 3) // Mettre les bits verts a 10-5
-                        | (pix.2.0[2] as u16 >> 3);               // Mettre les bits bleus a 4-0
-        converted_pixels.extend(rgb565.to_le_bytes());  // 2 octets
+    // Encodages possibles : (bits par pixel, palette, octets des pixels)
+    let mut candidates: Vec<(u8, Vec<u16>, Vec<u8>)> = vec![(16, Vec::new(), colors.iter().flat_map(|c| c.to_le_bytes()).collect())];
+
+    let mut palette: Vec<u16> = Vec::new();
+    for &color in &colors {
+        if !palette.contains(&color) {
+            palette.push(color);
+            if palette.len() > 256 {
+                break;
+            }
+        }
     }
+    if palette.len() <= 256 {
+        let bits_per_pixel: u8 = match palette.len() {
+            0..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
 
+        // Index sur `bits_per_pixel` bits, bits de poids fort en premier
+        let mut packed: Vec<u8> = Vec::new();
+        let mut used_bits = 8;
+        for color in &colors {
+            let index = palette.iter().position(|c| c == color).unwrap() as u8;
+            if used_bits == 8 {
+                packed.push(0);
+                used_bits = 0;
+            }
+            used_bits += bits_per_pixel;
+            *packed.last_mut().unwrap() |= index << (8 - used_bits);
+        }
+        candidates.push((bits_per_pixel, palette, packed));
+    }
+
+    // Garder l'encodage le plus petit (palette comprise)
+    let (bits_per_pixel, palette, compression, data) = candidates
+        .into_iter()
+        .flat_map(|(bits_per_pixel, palette, stream)| {
+            let unit = if bits_per_pixel == 16 { 2 } else { 1 };
+            [
+                (0u8, stream.clone()),
+                (1u8, eif2_rle_encode(&stream, unit)),
+                (2u8, eif2_lz_encode(&stream)),
+            ]
+            .map(|(compression, data)| (bits_per_pixel, palette.clone(), compression, data))
+        })
+        .min_by_key(|(_, palette, _, data)| palette.len() * 2 + data.len())
+        .unwrap();
+
+    let mut converted_pixels: Vec<u8> = Vec::new();
+
+    // En-tête EIF2 (20 octets)
+    converted_pixels.extend(utils::EIF2_MAGIC_NUMBER.to_le_bytes());     // Magic number (4 bytes)
+    converted_pixels.push(EIF2_VERSION);                                 // Version (1 byte)
+    converted_pixels.push(compression);                                  // Compression (1 byte)
+    converted_pixels.push(bits_per_pixel);                               // Bits par pixel (1 byte)
+    converted_pixels.push(color_key.is_some() as u8 | (mask.is_some() as u8) << 1); // Drapeaux (1 byte)
+    converted_pixels.extend((img.width() as u16).to_le_bytes());         // Width (2 bytes)
+    converted_pixels.extend((img.height() as u16).to_le_bytes());        // Height (2 bytes)
+    converted_pixels.extend((palette.len() as u16).to_le_bytes());       // Taille de la palette (2 bytes)
+    converted_pixels.extend(color_key.unwrap_or(0).to_le_bytes());       // Color key (2 bytes)
+    converted_pixels.extend((data.len() as u32).to_le_bytes());          // Taille des données (4 bytes)
+
+    // Palette, pixels puis masque alpha éventuel
+    palette.iter().for_each(|color| converted_pixels.extend(color.to_le_bytes()));
+    converted_pixels.extend(data);
+    if let Some(mask) = mask {
+        converted_pixels.extend(mask);
+    }
+
+    write_image(file_path, &converted_pixels);
+}
+
+/// Écrit une image convertie dans `OUT_DIR/assets/<nom>.eif`.
+fn write_image(file_path: &std::path::Path, converted_pixels: &[u8]) {
     // Nom du fichier sans le chemin
     let out_name = file_path.file_name().unwrap().to_str().unwrap();
     
@@ -70,10 +293,102 @@ fn convert_image(file_path: &std::path::Path) {
         .expect("Failed to create output assets/ directory");
 
     // Écrire le fichier converti au format .bin
-    fs::write(format!("{}/{}.eif", out_dir, out_name), converted_pixels.as_slice())
+    fs::write(format!("{}/{}.eif", out_dir, out_name), converted_pixels)
         .expect("Failed to write converted image file");
 }
 
+/// Convertit un atlas de police (`<nom>.png`) et sa liste de caractères (`<nom>.txt`) au format EFF1.
+///
+/// La première ligne du fichier `.txt` donne la taille d'une cellule (`<largeur>x<hauteur>`),
+/// chaque ligne suivante liste les caractères d'une rangée de l'atlas, de gauche à droite.
+/// Les pixels opaques (alpha >= 128) forment le glyphe, le fond doit être transparent.
+fn convert_font(image_path: &std::path::Path, chars_path: &std::path::Path) {
+    let img = ImageReader::open(image_path)
+        .unwrap_or_else(|err| panic!("Failed to open font image {}: {}", image_path.display(), err))
+        .decode()
+        .unwrap_or_else(|err| panic!("Failed to decode font image {}: {}", image_path.display(), err));
+
+    let descriptor = fs::read_to_string(chars_path)
+        .unwrap_or_else(|err| panic!("Failed to read font characters {}: {}", chars_path.display(), err));
+    let mut lines = descriptor.lines();
+
+    // Lire la taille des cellules sur la première ligne
+    let (cell_width, cell_height) = lines
+        .next()
+        .and_then(|line| line.trim().split_once('x'))
+        .and_then(|(w, h)| Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?)))
+        .unwrap_or_else(|| panic!("First line of {} must be the cell size (<width>x<height>)", chars_path.display()));
+    assert!(cell_width > 0 && cell_width <= 255 && cell_height > 0 && cell_height <= 255, "Font cell size must be between 1x1 and 255x255");
+
+    // (code point, largeur, avance, pixels du glyphe ligne par ligne)
+    let mut glyphs: Vec<(u32, u8, u8, Vec<bool>)> = Vec::new();
+
+    for (row, line) in lines.enumerate() {
+        for (column, character) in line.chars().enumerate() {
+            if glyphs.iter().any(|glyph| glyph.0 == character as u32) {
+                cargo_warn!("Duplicate character {:?} in {}, keeping the first one", character, chars_path.display());
+                continue;
+            }
+
+            let (x0, y0) = (column as u32 * cell_width, row as u32 * cell_height);
+            assert!(
+                x0 + cell_width <= img.width() && y0 + cell_height <= img.height(),
+                "Character {:?} of {} is outside of the font image", character, chars_path.display()
+            );
+
+            let ink = |x: u32, y: u32| img.get_pixel(x0 + x, y0 + y).0[3] >= 128;
+
+            // Rogner les colonnes vides à gauche et à droite (largeur proportionnelle)
+            let columns: Vec<u32> = (0..cell_width).filter(|&x| (0..cell_height).any(|y| ink(x, y))).collect();
+            let (first, width) = match (columns.first(), columns.last()) {
+                (Some(&first), Some(&last)) => (first, last - first + 1),
+                _ => (0, 0), // Glyphe vide (espace)
+            };
+            let advance = if width == 0 { cell_width.div_ceil(2) } else { width + FONT_GLYPH_SPACING };
+
+            let pixels = (0..cell_height)
+                .flat_map(|y| (first..first + width).map(move |x| (x, y)))
+                .map(|(x, y)| ink(x, y))
+                .collect();
+
+            glyphs.push((character as u32, width as u8, advance.min(255) as u8, pixels));
+        }
+    }
+
+    // Trier par code point pour la recherche dichotomique à l'exécution
+    glyphs.sort_by_key(|glyph| glyph.0);
+
+    let mut header: Vec<u8> = Vec::new();
+    let mut bitmaps: Vec<u8> = Vec::new();
+
+    header.extend(utils::EFF1_MAGIC_NUMBER.to_le_bytes()); // Magic number (4 bytes)
+    header.extend((cell_height as u16).to_le_bytes());     // Hauteur des glyphes (2 bytes)
+    header.extend((glyphs.len() as u16).to_le_bytes());    // Nombre de glyphes (2 bytes)
+
+    for (code_point, width, advance, pixels) in &glyphs {
+        header.extend(code_point.to_le_bytes());             // Code point (4 bytes)
+        header.push(*width);                                 // Largeur (1 byte)
+        header.push(*advance);                               // Avance (1 byte)
+        header.extend((bitmaps.len() as u32).to_le_bytes()); // Position des pixels (4 bytes)
+
+        // Pixels sur 1 bit, bit de poids fort en premier, chaque glyphe commence sur un octet
+        for chunk in pixels.chunks(8) {
+            bitmaps.push(chunk.iter().enumerate().fold(0u8, |byte, (i, &on)| byte | ((on as u8) << (7 - i))));
+        }
+    }
+
+    header.extend(bitmaps);
+
+    // Définir le dossier de sortie des polices converties
+    let out_dir = format!("{}/assets/{}", std::env::var("OUT_DIR").unwrap(), FONTS_DIR);
+    fs::create_dir_all(&out_dir)
+        .expect("Failed to create output assets/fonts/ directory");
+
+    let out_name = image_path.file_stem().unwrap().to_str().unwrap();
+    fs::write(format!("{}/{}.eff", out_dir, out_name), header.as_slice())
+        .expect("Failed to write converted font file");
+}
+
 pub fn setup() {
     setup_with_options(None, None, None);
 }
@@ -98,6 +413,9 @@ pub fn setup_with_options(
         .to_string_lossy()
         .to_string();
 
+    // Reconvertir les images si le format demandé change
+    println!("cargo:rerun-if-env-changed={}", IMAGE_FORMAT_VAR);
+
     // Créer le dossier assets/ s'il n'existe pas
 
     fs::create_dir_all(&asset_dir)
@@ -115,6 +433,11 @@ pub fn setup_with_options(
 
         let path = entry.path();
 
+        // Les sous-dossiers (ex: fonts/) sont traités séparément
+        if path.is_dir() {
+            continue;
+        }
+
         // Ignorer le fichier s'il n'a pas d'extension
         // Definir 'ext'
         let ext = match path.extension() {
@@ -181,8 +504,22 @@ pub fn setup_with_options(
         }
 
     };
-        
 
+    // Convertir les polices présentes dans assets/fonts/ (atlas .png + liste de caractères .txt)
+    let fonts_dir = asset_dir.join(FONTS_DIR);
+    if let Ok(font_entries) = fs::read_dir(&fonts_dir) {
+        for entry in font_entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "png") {
+                let chars_path = path.with_extension("txt");
+                if !chars_path.exists() {
+                    cargo_warn!("Ignoring font without character list ({}): {}", chars_path.display(), path.display());
+                    continue;
+                }
+                convert_font(&path, &chars_path);
+            }
+        }
+    }
 
     // Compilation et linkage des fichiers C/C++ présent dans src/libs/, src/lib/, src/c ou src/cpp
     
@@ -1,5 +1,5 @@
 
-use super::{Color, Point, Rect, Image};
+use super::{Color, Point, Rect, Image, ImageRef};
 
 #[cfg(target_os = "none")]
 use alloc::vec::Vec;
@@ -10,8 +10,31 @@ use alloc::ffi::CString;
 #[cfg(not(target_os = "none"))]
 use std::ffi::CString;
 
+#[cfg(not(all(not(target_os = "none"), feature = "host-display")))]
 use core::ffi::c_char;
 
+mod blit;
+pub mod draw;
+mod framebuffer;
+mod layout;
+pub use blit::{push_image_region, push_image_scaled, tile_image};
+pub use framebuffer::{FrameBuffer, TILE_BYTES, TILE_HEIGHT, TILE_WIDTH};
+pub use layout::{draw_text_box, text_size, wrap, Align, HorizontalAlign, TextColors, VerticalAlign};
+
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::Display;
+
+// In-memory backend replacing Epsilon on the host
+#[cfg(all(not(target_os = "none"), feature = "host-display"))]
+pub mod host;
+#[cfg(all(not(target_os = "none"), feature = "host-display"))]
+use host::{
+    eadk_display_draw_string, eadk_display_pull_rect, eadk_display_push_rect, eadk_display_push_rect_uniform,
+    eadk_display_wait_for_vblank,
+};
+
 pub fn push_rect(rect: Rect, pixels: &[Color]) {
     unsafe {
         eadk_display_push_rect(rect, pixels.as_ptr());
@@ -65,13 +88,16 @@ pub fn draw_string(
     }
 }
 
+/// Draws the whole image with its top-left corner at `point`, clipped to the screen.
 pub fn push_image(image: &Image, point: Point) {
-    push_rect(
-        image.for_coordinates(point.x, point.y),
-        &image.get_pixels()
-    );
+    push_image_region(image, image.for_coordinates(0, 0), point);
+}
+
+pub fn push_image_ref(image: &ImageRef, point: Point) {
+    image.draw(point);
 }
 
+#[cfg(not(all(not(target_os = "none"), feature = "host-display")))]
 unsafe extern "C" {
     fn eadk_display_push_rect_uniform(rect: Rect, color: Color);
     fn eadk_display_push_rect(rect: Rect, color: *const Color);
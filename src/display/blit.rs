@@ -0,0 +1,129 @@
+use crate::{Image, Point, Rect, COLOR_BLACK, IMAGE_SCRATCH_PIXELS, SCREEN_RECT};
+
+/// Clips `source` to the image bounds, then to the screen when drawn at `point` with `scale`.
+///
+/// Returns the visible part of the source (in image pixels), `None` if nothing is visible.
+fn clip_source(image: &Image, source: Rect, point: Point, scale: u16) -> Option<Rect> {
+    let x = source.x.min(image.width);
+    let y = source.y.min(image.height);
+    let width = source.width.min(image.width - x);
+    let height = source.height.min(image.height - y);
+
+    // Number of source pixels that fit on the screen, rounded up to keep partial blocks
+    let screen_width = SCREEN_RECT.width.saturating_sub(point.x).div_ceil(scale);
+    let screen_height = SCREEN_RECT.height.saturating_sub(point.y).div_ceil(scale);
+
+    let clipped = Rect { x, y, width: width.min(screen_width), height: height.min(screen_height) };
+    (clipped.width > 0 && clipped.height > 0).then_some(clipped)
+}
+
+/// Draws the `source` zone of `image` with its top-left corner at `point`.
+///
+/// The zone is clipped to the image and to the screen, nothing is drawn past 320x240.
+/// Rows are pushed straight from the image pixels, without any copy.
+///
+/// ```rust,ignore
+/// // Draw the second 16x16 cell of a sprite sheet
+/// eadkp::display::push_image_region(
+///     &sheet,
+///     eadkp::Rect { x: 16, y: 0, width: 16, height: 16 },
+///     eadkp::Point { x: 100, y: 50 },
+/// );
+/// ```
+pub fn push_image_region(image: &Image, source: Rect, point: Point) {
+    let Some(source) = clip_source(image, source, point, 1) else {
+        return;
+    };
+
+    let image_width = image.width as usize;
+    let start = source.y as usize * image_width + source.x as usize;
+
+    // Full rows: the pixels are contiguous in the image
+    if source.x == 0 && source.width == image.width {
+        let end = start + image_width * source.height as usize;
+        super::push_rect(
+            Rect { height: source.height, ..image.for_coordinates(point.x, point.y) },
+            &image.pixels[start..end],
+        );
+        return;
+    }
+
+    for row in 0..source.height as usize {
+        let line = start + row * image_width;
+        super::push_rect(
+            Rect { x: point.x, y: point.y + row as u16, width: source.width, height: 1 },
+            &image.pixels[line..line + source.width as usize],
+        );
+    }
+}
+
+/// Draws the `source` zone of `image` enlarged `scale` times (nearest neighbour) with its top-left corner at `point`.
+///
+/// Each image pixel becomes a `scale`x`scale` block. The result is clipped to the screen.
+/// A `scale` of 0 draws nothing, a `scale` of 1 is the same as [`push_image_region`].
+pub fn push_image_scaled(image: &Image, source: Rect, point: Point, scale: u16) {
+    if scale == 0 {
+        return;
+    }
+    if scale == 1 {
+        push_image_region(image, source, point);
+        return;
+    }
+    let Some(source) = clip_source(image, source, point, scale) else {
+        return;
+    };
+
+    let visible_width = (source.width * scale).min(SCREEN_RECT.width - point.x) as usize;
+    let visible_height = (source.height * scale).min(SCREEN_RECT.height - point.y);
+    let rows_per_band = (IMAGE_SCRATCH_PIXELS / visible_width) as u16;
+    let mut scratch = [COLOR_BLACK; IMAGE_SCRATCH_PIXELS];
+
+    for source_row in 0..source.height {
+        let line = (source.y + source_row) as usize * image.width as usize + source.x as usize;
+
+        // Enlarge one image row
+        for (x, pixel) in scratch.iter_mut().enumerate().take(visible_width) {
+            *pixel = image.pixels[line + x / scale as usize];
+        }
+
+        // Repeat it on the block height, by bands that fit in the scratch buffer
+        let first_row = source_row * scale;
+        let block_height = scale.min(visible_height - first_row);
+        let band_height = block_height.min(rows_per_band);
+        for row in 1..band_height as usize {
+            scratch.copy_within(0..visible_width, row * visible_width);
+        }
+
+        let mut row = 0;
+        while row < block_height {
+            let rows = band_height.min(block_height - row);
+            super::push_rect(
+                Rect { x: point.x, y: point.y + first_row + row, width: visible_width as u16, height: rows },
+                &scratch[..visible_width * rows as usize],
+            );
+            row += rows;
+        }
+    }
+}
+
+/// Fills `rect` by repeating `image`, starting from its top-left corner (for backgrounds).
+///
+/// The last row and column of tiles are cut at the edge of `rect`, and everything is clipped to the screen.
+pub fn tile_image(image: &Image, rect: Rect) {
+    if image.width == 0 || image.height == 0 {
+        return;
+    }
+
+    let right = (rect.x as u32 + rect.width as u32).min(SCREEN_RECT.width as u32) as u16;
+    let bottom = (rect.y as u32 + rect.height as u32).min(SCREEN_RECT.height as u32) as u16;
+
+    for y in (rect.y..bottom).step_by(image.height as usize) {
+        for x in (rect.x..right).step_by(image.width as usize) {
+            push_image_region(
+                image,
+                Rect { x: 0, y: 0, width: right - x, height: bottom - y },
+                Point { x, y },
+            );
+        }
+    }
+}
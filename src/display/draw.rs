@@ -0,0 +1,304 @@
+//! # 2D drawing primitives
+//!
+//! Lines, circles, ellipses, triangles, polygons and rounded rectangles drawn
+//! straight to the screen. Every primitive is clipped to [`SCREEN_RECT`] and
+//! its pixels are gathered in horizontal (or vertical) runs pushed with
+//! `push_rect_uniform`, so a filled shape costs one call per row.
+//!
+//! ```rust,ignore
+//! use eadkp::{display::draw, Point, Rect, COLOR_BLUE, COLOR_RED};
+//!
+//! draw::line(Point { x: 10, y: 10 }, Point { x: 200, y: 120 }, COLOR_RED);
+//! draw::fill_circle(Point { x: 160, y: 120 }, 40, COLOR_BLUE);
+//! draw::rounded_rect(Rect { x: 20, y: 150, width: 120, height: 60 }, 8, COLOR_RED);
+//! ```
+
+#[cfg(target_os = "none")]
+use alloc::vec::Vec;
+
+use crate::{Color, Point, Rect, SCREEN_RECT};
+
+// ==================================================================================
+// ==================================== LINES =======================================
+// ==================================================================================
+
+/// Draws a one pixel wide line from `start` to `end` (both included), with Bresenham's algorithm.
+pub fn line(start: Point, end: Point, color: Color) {
+    line_i32((start.x as i32, start.y as i32), (end.x as i32, end.y as i32), color);
+}
+
+/// Draws a line `thickness` pixels wide, centered on the segment from `start` to `end`.
+pub fn thick_line(start: Point, end: Point, thickness: u16, color: Color) {
+    if thickness <= 1 {
+        return line(start, end, color);
+    }
+
+    let (x0, y0) = (start.x as i64, start.y as i64);
+    let (x1, y1) = (end.x as i64, end.y as i64);
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let length = ((dx * dx + dy * dy) as u64).isqrt() as i64;
+
+    if length == 0 {
+        let half = thickness as i32 / 2;
+        return fill_span_rect(x0 as i32 - half, y0 as i32 - half, thickness as i32, thickness as i32, color);
+    }
+
+    // Perpendicular vector of length thickness, split on both sides of the segment
+    let thickness = thickness as i64;
+    let (nx, ny) = (div_round(-dy * thickness, length), div_round(dx * thickness, length));
+    let (hx, hy) = (nx / 2, ny / 2);
+
+    fill_polygon_i32(
+        &[
+            ((x0 + hx) as i32, (y0 + hy) as i32),
+            ((x1 + hx) as i32, (y1 + hy) as i32),
+            ((x1 + hx - nx) as i32, (y1 + hy - ny) as i32),
+            ((x0 + hx - nx) as i32, (y0 + hy - ny) as i32),
+        ],
+        color,
+    );
+}
+
+fn line_i32((x0, y0): (i32, i32), (x1, y1): (i32, i32), color: Color) {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let step_x = if x0 < x1 { 1 } else { -1 };
+    let step_y = if y0 < y1 { 1 } else { -1 };
+    let horizontal = dx >= -dy;
+
+    let (mut x, mut y) = (x0, y0);
+    let mut error = dx + dy;
+    // First pixel of the current run
+    let (mut run_x, mut run_y) = (x, y);
+
+    loop {
+        if x == x1 && y == y1 {
+            break;
+        }
+
+        let doubled = 2 * error;
+        let mut moved_x = false;
+        let mut moved_y = false;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+            moved_x = true;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+            moved_y = true;
+        }
+
+        // A run ends when the line leaves its row (or column for steep lines)
+        let run_ended = if horizontal { moved_y } else { moved_x };
+        if run_ended {
+            let (last_x, last_y) = (x - if moved_x { step_x } else { 0 }, y - if moved_y { step_y } else { 0 });
+            run(run_x, run_y, last_x, last_y, color);
+            (run_x, run_y) = (x, y);
+        }
+    }
+
+    run(run_x, run_y, x, y, color);
+}
+
+/// Pushes a horizontal or vertical run between two pixels (both included).
+fn run(x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+    fill_span_rect(x0.min(x1), y0.min(y1), (x1 - x0).abs() + 1, (y1 - y0).abs() + 1, color);
+}
+
+// ==================================================================================
+// =============================== CIRCLES & ELLIPSES ===============================
+// ==================================================================================
+
+/// Draws the outline of a circle.
+pub fn circle(center: Point, radius: u16, color: Color) {
+    rounded_shape(center.x as i32, center.y as i32, center.x as i32, center.y as i32, radius, radius, false, color);
+}
+
+/// Draws a filled circle.
+pub fn fill_circle(center: Point, radius: u16, color: Color) {
+    rounded_shape(center.x as i32, center.y as i32, center.x as i32, center.y as i32, radius, radius, true, color);
+}
+
+/// Draws the outline of an axis-aligned ellipse.
+pub fn ellipse(center: Point, radius_x: u16, radius_y: u16, color: Color) {
+    rounded_shape(center.x as i32, center.y as i32, center.x as i32, center.y as i32, radius_x, radius_y, false, color);
+}
+
+/// Draws a filled axis-aligned ellipse.
+pub fn fill_ellipse(center: Point, radius_x: u16, radius_y: u16, color: Color) {
+    rounded_shape(center.x as i32, center.y as i32, center.x as i32, center.y as i32, radius_x, radius_y, true, color);
+}
+
+// ==================================================================================
+// ============================== ROUNDED RECTANGLES ================================
+// ==================================================================================
+
+/// Draws the outline of a rectangle with rounded corners.
+///
+/// The radius is reduced to fit in the rectangle.
+pub fn rounded_rect(rect: Rect, radius: u16, color: Color) {
+    rounded_rect_shape(rect, radius, false, color);
+}
+
+/// Draws a filled rectangle with rounded corners.
+///
+/// The radius is reduced to fit in the rectangle.
+pub fn fill_rounded_rect(rect: Rect, radius: u16, color: Color) {
+    rounded_rect_shape(rect, radius, true, color);
+}
+
+fn rounded_rect_shape(rect: Rect, radius: u16, fill: bool, color: Color) {
+    if rect.width == 0 || rect.height == 0 {
+        return;
+    }
+
+    let radius = radius.min((rect.width - 1) / 2).min((rect.height - 1) / 2);
+    let (left, top) = (rect.x as i32 + radius as i32, rect.y as i32 + radius as i32);
+    let right = rect.x as i32 + rect.width as i32 - 1 - radius as i32;
+    let bottom = rect.y as i32 + rect.height as i32 - 1 - radius as i32;
+
+    rounded_shape(left, top, right, bottom, radius, radius, fill, color);
+}
+
+/// Draws a rectangle from `(left, top)` to `(right, bottom)` grown by elliptic corners.
+///
+/// A circle or an ellipse is the case where the inner rectangle is a single point.
+#[allow(clippy::too_many_arguments)]
+fn rounded_shape(left: i32, top: i32, right: i32, bottom: i32, radius_x: u16, radius_y: u16, fill: bool, color: Color) {
+    let (rx, ry) = (radius_x as i32, radius_y as i32);
+
+    // Half-width of the shape on a row, -1 outside
+    let half_width = |y: i32| -> i32 {
+        if y < top - ry || y > bottom + ry {
+            -1
+        } else if y < top {
+            corner_width(radius_x, radius_y, top - y)
+        } else if y > bottom {
+            corner_width(radius_x, radius_y, y - bottom)
+        } else {
+            rx
+        }
+    };
+
+    let first_row = (top - ry).max(0);
+    let last_row = (bottom + ry).min(SCREEN_RECT.height as i32 - 1);
+
+    for y in first_row..=last_row {
+        let width = half_width(y);
+
+        // Outline pixels have a neighbour outside the shape, in the row above or below
+        let inner = if fill { 0 } else { width.min(half_width(y - 1).min(half_width(y + 1)) + 1) };
+
+        if inner == 0 {
+            hspan(left - width, right + width, y, color);
+        } else {
+            hspan(left - width, left - inner, y, color);
+            hspan(right + inner, right + width, y, color);
+        }
+    }
+}
+
+/// Half-width of an ellipse at `dy` rows from its center (`dy <= radius_y`).
+///
+/// The radii are grown by half a pixel so the extreme rows and columns are not reduced to a single pixel.
+fn corner_width(radius_x: u16, radius_y: u16, dy: i32) -> i32 {
+    let a = 2 * radius_x as u128 + 1;
+    let b = 2 * radius_y as u128 + 1;
+    let dy = dy as u128;
+
+    ((a * a * (b * b - 4 * dy * dy)) / (4 * b * b)).isqrt() as i32
+}
+
+// ==================================================================================
+// ============================= TRIANGLES & POLYGONS ===============================
+// ==================================================================================
+
+/// Draws the outline of a triangle.
+pub fn triangle(a: Point, b: Point, c: Point, color: Color) {
+    polygon(&[a, b, c], color);
+}
+
+/// Draws a filled triangle.
+pub fn fill_triangle(a: Point, b: Point, c: Point, color: Color) {
+    fill_polygon(&[a, b, c], color);
+}
+
+/// Draws the outline of a closed polygon.
+pub fn polygon(points: &[Point], color: Color) {
+    for (index, &point) in points.iter().enumerate() {
+        let next = points[(index + 1) % points.len()];
+        line(point, next, color);
+    }
+}
+
+/// Draws a filled polygon (even-odd rule), row by row with a scanline algorithm.
+///
+/// Pixels are filled when their center is inside the polygon.
+pub fn fill_polygon(points: &[Point], color: Color) {
+    let points: Vec<(i32, i32)> = points.iter().map(|point| (point.x as i32, point.y as i32)).collect();
+    fill_polygon_i32(&points, color);
+}
+
+fn fill_polygon_i32(points: &[(i32, i32)], color: Color) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let first_row = points.iter().map(|point| point.1).min().unwrap_or(0).max(0);
+    let last_row = points.iter().map(|point| point.1).max().unwrap_or(0).min(SCREEN_RECT.height as i32 - 1);
+    let mut crossings: Vec<i32> = Vec::new();
+
+    for y in first_row..=last_row {
+        // Crossings of the edges with the horizontal line through the pixel centers
+        crossings.clear();
+        for (index, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(index + 1) % points.len()];
+            if (y0 <= y) == (y1 <= y) {
+                continue;
+            }
+
+            // First pixel whose center is right of the crossing, in 16.16 fixed point
+            let x = ((x0 as i64) << 16) + (((2 * (y - y0) + 1) as i64 * (x1 - x0) as i64) << 16) / (2 * (y1 - y0) as i64);
+            crossings.push(((x - 0x8000 + 0xFFFF) >> 16) as i32);
+        }
+
+        crossings.sort_unstable();
+        for pair in crossings.chunks_exact(2) {
+            hspan(pair[0], pair[1] - 1, y, color);
+        }
+    }
+}
+
+// ==================================================================================
+// ==================================== RUNS ========================================
+// ==================================================================================
+
+/// Pushes the pixels from `x0` to `x1` (both included) of a row, clipped to the screen.
+fn hspan(x0: i32, x1: i32, y: i32, color: Color) {
+    fill_span_rect(x0, y, x1 - x0 + 1, 1, color);
+}
+
+/// Fills a rectangle given in signed coordinates, clipped to the screen.
+pub(crate) fn fill_span_rect(x: i32, y: i32, width: i32, height: i32, color: Color) {
+    let x0 = x.max(0);
+    let y0 = y.max(0);
+    let x1 = (x + width).min(SCREEN_RECT.width as i32);
+    let y1 = (y + height).min(SCREEN_RECT.height as i32);
+
+    if x0 < x1 && y0 < y1 {
+        super::push_rect_uniform(
+            Rect { x: x0 as u16, y: y0 as u16, width: (x1 - x0) as u16, height: (y1 - y0) as u16 },
+            color,
+        );
+    }
+}
+
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    if (numerator < 0) == (denominator < 0) {
+        (numerator + denominator / 2) / denominator
+    } else {
+        (numerator - denominator / 2) / denominator
+    }
+}
@@ -0,0 +1,303 @@
+#[cfg(target_os = "none")]
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::{Color, Point, Rect, SCREEN_RECT};
+
+/// Width of a framebuffer tile in pixels
+pub const TILE_WIDTH: u16 = 32;
+/// Height of a framebuffer tile in pixels
+pub const TILE_HEIGHT: u16 = 24;
+/// Memory used by one resident tile, in bytes
+pub const TILE_BYTES: usize = TILE_WIDTH as usize * TILE_HEIGHT as usize * core::mem::size_of::<Color>();
+
+const TILE_COLUMNS: u16 = SCREEN_RECT.width / TILE_WIDTH;
+const TILE_ROWS: u16 = SCREEN_RECT.height / TILE_HEIGHT;
+const TILE_COUNT: usize = TILE_COLUMNS as usize * TILE_ROWS as usize;
+const TILE_PIXELS: usize = TILE_WIDTH as usize * TILE_HEIGHT as usize;
+const FULL_TILE: Region = Region { x0: 0, y0: 0, x1: TILE_WIDTH, y1: TILE_HEIGHT };
+
+/// Changed area of a tile, in tile coordinates (end excluded)
+#[derive(Clone, Copy)]
+struct Region {
+    x0: u16,
+    y0: u16,
+    x1: u16,
+    y1: u16,
+}
+
+impl Region {
+    fn union(self, other: Region) -> Region {
+        Region {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tile {
+    /// Pixels of the tile, `None` when the tile is not resident
+    pixels: Option<Box<[Color]>>,
+    /// Area changed since the last flush
+    dirty: Option<Region>,
+    /// The content of this tile is only the color given to the last `clear`
+    cleared: bool,
+    /// The tile is not resident and must be filled with the clear color on the next flush
+    repaint: bool,
+    /// Uniform color known to be on the screen under this tile
+    on_screen: Option<Color>,
+}
+
+/// # Off-screen framebuffer with dirty-rectangle flushing
+///
+/// Drawing happens in memory and [`FrameBuffer::flush`] pushes only the
+/// changed areas to the LCD, right after `wait_for_vblank`. Erasing and
+/// redrawing an object in the same frame no longer flickers.
+///
+/// The screen is split into tiles of [`TILE_WIDTH`]x[`TILE_HEIGHT`] pixels,
+/// allocated when first drawn to. The memory budget limits how many tiles
+/// stay resident: when it is reached, the changed tiles are pushed early and
+/// released. [`FrameBuffer::full`] keeps the whole screen (150 KB), smaller
+/// budgets fit in the heap set up by `eadk_setup!`.
+///
+/// A tile is loaded with `pull_rect` when only part of it is drawn, unless
+/// its content is known from a previous [`FrameBuffer::clear`]. The clear is
+/// composed into the tiles, so a frame made of `clear` and redraws only
+/// pushes the tiles that differ from the screen.
+pub struct FrameBuffer {
+    tiles: Vec<Tile>,
+    max_resident: usize,
+    resident: usize,
+    /// Color of the last `clear`, used to initialize the tiles still marked `cleared`
+    clear_color: Color,
+    /// Row buffer used to push a partial tile
+    scratch: Vec<Color>,
+}
+
+impl FrameBuffer {
+    /// Memory budget needed to keep the whole screen resident
+    pub const FULL_SCREEN_BUDGET: usize = TILE_COUNT * TILE_BYTES;
+
+    /// Creates a framebuffer using at most `budget` bytes of tiles (at least one tile).
+    pub fn new(budget: usize) -> Self {
+        let mut tiles = Vec::with_capacity(TILE_COUNT);
+        tiles.resize_with(TILE_COUNT, Tile::default);
+
+        FrameBuffer {
+            tiles,
+            max_resident: (budget / TILE_BYTES).clamp(1, TILE_COUNT),
+            resident: 0,
+            clear_color: crate::COLOR_BLACK,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Creates a framebuffer holding the whole screen (320x240, 150 KB).
+    pub fn full() -> Self {
+        Self::new(Self::FULL_SCREEN_BUDGET)
+    }
+
+    /// Memory budget of the tiles, in bytes
+    pub fn budget(&self) -> usize {
+        self.max_resident * TILE_BYTES
+    }
+
+    /// Returns `true` if some drawing has not been flushed yet.
+    pub fn is_dirty(&self) -> bool {
+        self.tiles.iter().any(|tile| tile.dirty.is_some() || tile.repaint)
+    }
+
+    /// Fills the whole screen with `color`.
+    ///
+    /// Resident tiles are filled in memory, the others are pushed as uniform rectangles on the
+    /// next flush. Tiles whose screen already shows `color` are not pushed again.
+    pub fn clear(&mut self, color: Color) {
+        for tile in self.tiles.iter_mut() {
+            let unchanged = tile.on_screen == Some(color);
+            match tile.pixels.as_deref_mut() {
+                Some(pixels) => {
+                    pixels.fill(color);
+                    tile.dirty = if unchanged { None } else { Some(FULL_TILE) };
+                }
+                None => {
+                    tile.dirty = None;
+                    tile.repaint = !unchanged;
+                }
+            }
+            tile.cleared = true;
+        }
+        self.clear_color = color;
+    }
+
+    /// Sets one pixel. Pixels outside the screen are ignored.
+    pub fn set_pixel(&mut self, point: Point, color: Color) {
+        self.fill_rect(Rect { x: point.x, y: point.y, width: 1, height: 1 }, color);
+    }
+
+    /// Returns the color of one pixel, loading its tile if needed.
+    pub fn get_pixel(&mut self, point: Point) -> Color {
+        if point.x >= SCREEN_RECT.width || point.y >= SCREEN_RECT.height {
+            return crate::COLOR_BLACK;
+        }
+
+        let index = tile_index(point.x / TILE_WIDTH, point.y / TILE_HEIGHT);
+        let pixels = self.tile_pixels(index, false);
+        pixels[(point.y % TILE_HEIGHT) as usize * TILE_WIDTH as usize + (point.x % TILE_WIDTH) as usize]
+    }
+
+    /// Fills a rectangle with a uniform color, clipped to the screen.
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.draw(rect, |pixels, _, _| pixels.fill(color));
+    }
+
+    /// Copies pixels (row by row, like `display::push_rect`) into a rectangle, clipped to the screen.
+    pub fn push_rect(&mut self, rect: Rect, pixels: &[Color]) {
+        debug_assert!(pixels.len() >= rect.width as usize * rect.height as usize, "push_rect: not enough pixels");
+
+        self.draw(rect, |row, x, y| {
+            let start = (y - rect.y) as usize * rect.width as usize + (x - rect.x) as usize;
+            row.copy_from_slice(&pixels[start..start + row.len()]);
+        });
+    }
+
+    /// Pushes the changed areas to the screen after waiting for the vertical blank.
+    pub fn flush(&mut self) {
+        if !self.is_dirty() {
+            return;
+        }
+
+        super::wait_for_vblank();
+        self.push_dirty();
+    }
+
+    /// Runs `paint(row, x, y)` on every clipped row span of `rect`, tile by tile.
+    fn draw(&mut self, rect: Rect, mut paint: impl FnMut(&mut [Color], u16, u16)) {
+        let Some(rect) = clip(rect) else { return };
+        let (x_end, y_end) = (rect.x + rect.width, rect.y + rect.height);
+
+        for tile_y in rect.y / TILE_HEIGHT..=(y_end - 1) / TILE_HEIGHT {
+            for tile_x in rect.x / TILE_WIDTH..=(x_end - 1) / TILE_WIDTH {
+                // Part of the rectangle inside this tile, in tile coordinates
+                let (origin_x, origin_y) = (tile_x * TILE_WIDTH, tile_y * TILE_HEIGHT);
+                let region = Region {
+                    x0: rect.x.max(origin_x) - origin_x,
+                    y0: rect.y.max(origin_y) - origin_y,
+                    x1: x_end.min(origin_x + TILE_WIDTH) - origin_x,
+                    y1: y_end.min(origin_y + TILE_HEIGHT) - origin_y,
+                };
+                let covers_tile = region.x0 == 0 && region.y0 == 0
+                    && region.x1 == TILE_WIDTH && region.y1 == TILE_HEIGHT;
+
+                let index = tile_index(tile_x, tile_y);
+                let pixels = self.tile_pixels(index, covers_tile);
+                for y in region.y0..region.y1 {
+                    let row = y as usize * TILE_WIDTH as usize;
+                    paint(
+                        &mut pixels[row + region.x0 as usize..row + region.x1 as usize],
+                        origin_x + region.x0,
+                        origin_y + y,
+                    );
+                }
+
+                let tile = &mut self.tiles[index];
+                tile.dirty = Some(tile.dirty.map_or(region, |dirty| dirty.union(region)));
+            }
+        }
+    }
+
+    /// Returns the pixels of a tile, making it resident first.
+    ///
+    /// When `overwritten` is true, the whole tile is about to be drawn and its content is not loaded.
+    fn tile_pixels(&mut self, index: usize, overwritten: bool) -> &mut [Color] {
+        if self.tiles[index].pixels.is_none() {
+            // Budget reached: push the changes and release the tiles
+            if self.resident >= self.max_resident {
+                self.push_dirty();
+                for tile in self.tiles.iter_mut() {
+                    tile.pixels = None;
+                }
+                self.resident = 0;
+            }
+
+            let tile = &mut self.tiles[index];
+            let pixels: Box<[Color]> = if overwritten || tile.cleared {
+                vec![self.clear_color; TILE_PIXELS].into_boxed_slice()
+            } else {
+                super::pull_rect(tile_rect(index)).into_boxed_slice()
+            };
+
+            // The pending clear is now part of the pixels, pushed with them
+            if core::mem::take(&mut tile.repaint) {
+                tile.dirty = Some(tile.dirty.map_or(FULL_TILE, |dirty| dirty.union(FULL_TILE)));
+            }
+            tile.pixels = Some(pixels);
+            self.resident += 1;
+        }
+
+        self.tiles[index].pixels.as_deref_mut().unwrap_or(&mut [])
+    }
+
+    /// Pushes the pending clears and the changed area of every tile.
+    fn push_dirty(&mut self) {
+        for index in 0..TILE_COUNT {
+            let tile = &mut self.tiles[index];
+            if core::mem::take(&mut tile.repaint) {
+                super::push_rect_uniform(tile_rect(index), self.clear_color);
+                tile.on_screen = Some(self.clear_color);
+                continue;
+            }
+
+            let (Some(region), Some(pixels)) = (tile.dirty.take(), tile.pixels.as_deref()) else { continue };
+            tile.cleared = false;
+            tile.on_screen = None;
+
+            let origin = tile_rect(index);
+            let rect = Rect {
+                x: origin.x + region.x0,
+                y: origin.y + region.y0,
+                width: region.x1 - region.x0,
+                height: region.y1 - region.y0,
+            };
+
+            // Full-width regions are contiguous in the tile, others are gathered row by row
+            if rect.width == TILE_WIDTH {
+                let start = region.y0 as usize * TILE_WIDTH as usize;
+                super::push_rect(rect, &pixels[start..start + rect.width as usize * rect.height as usize]);
+            } else {
+                self.scratch.clear();
+                for y in region.y0..region.y1 {
+                    let row = y as usize * TILE_WIDTH as usize;
+                    self.scratch.extend_from_slice(&pixels[row + region.x0 as usize..row + region.x1 as usize]);
+                }
+                super::push_rect(rect, &self.scratch);
+            }
+        }
+    }
+}
+
+fn tile_index(tile_x: u16, tile_y: u16) -> usize {
+    tile_y as usize * TILE_COLUMNS as usize + tile_x as usize
+}
+
+fn tile_rect(index: usize) -> Rect {
+    Rect {
+        x: (index % TILE_COLUMNS as usize) as u16 * TILE_WIDTH,
+        y: (index / TILE_COLUMNS as usize) as u16 * TILE_HEIGHT,
+        width: TILE_WIDTH,
+        height: TILE_HEIGHT,
+    }
+}
+
+/// Clips a rectangle to the screen, `None` if nothing is left.
+fn clip(rect: Rect) -> Option<Rect> {
+    let x_end = (rect.x as u32 + rect.width as u32).min(SCREEN_RECT.width as u32) as u16;
+    let y_end = (rect.y as u32 + rect.height as u32).min(SCREEN_RECT.height as u32) as u16;
+
+    if rect.x >= x_end || rect.y >= y_end {
+        return None;
+    }
+
+    Some(Rect { x: rect.x, y: rect.y, width: x_end - rect.x, height: y_end - rect.y })
+}
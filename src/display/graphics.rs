@@ -0,0 +1,203 @@
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{raw::RawU16, IntoStorage, Rgb565},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use super::FrameBuffer;
+use crate::{Color, Point, Rect, SCREEN_RECT};
+
+impl From<Rgb565> for Color {
+    fn from(color: Rgb565) -> Self {
+        Color { rgb565: color.into_storage() }
+    }
+}
+
+impl From<Color> for Rgb565 {
+    fn from(color: Color) -> Self {
+        Rgb565::from(RawU16::new(color.rgb565))
+    }
+}
+
+/// # LCD screen as an `embedded-graphics` draw target
+///
+/// Drawing goes straight to the screen: uniform fills use `push_rect_uniform`
+/// and contiguous areas are pushed row by row with `push_rect`.
+///
+/// ```rust,ignore
+/// use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}, pixelcolor::Rgb565};
+///
+/// Circle::new(Point::new(100, 60), 80)
+///     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+///     .draw(&mut eadkp::display::Display)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Display;
+
+impl OriginDimensions for Display {
+    fn size(&self) -> Size {
+        screen_size()
+    }
+}
+
+impl DrawTarget for Display {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // Horizontally adjacent pixels are gathered in one span
+        let mut span = [crate::COLOR_BLACK; SCREEN_RECT.width as usize];
+        let mut span_start = Point { x: 0, y: 0 };
+        let mut span_len = 0usize;
+
+        for Pixel(point, color) in pixels {
+            let Some(point) = to_screen(point) else { continue };
+
+            let continues = span_len > 0 && point.y == span_start.y && point.x as usize == span_start.x as usize + span_len;
+            if !continues {
+                push_span(span_start, &span[..span_len]);
+                span_start = point;
+                span_len = 0;
+            }
+
+            span[span_len] = color.into();
+            span_len += 1;
+        }
+
+        push_span(span_start, &span[..span_len]);
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        fill_rows(area, colors, super::push_rect);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if let Some(rect) = clip(area) {
+            super::push_rect_uniform(rect, color.into());
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        super::push_rect_uniform(SCREEN_RECT, color.into());
+        Ok(())
+    }
+}
+
+impl OriginDimensions for FrameBuffer {
+    fn size(&self) -> Size {
+        screen_size()
+    }
+}
+
+/// Drawing goes to the framebuffer: call [`FrameBuffer::flush`] to show it.
+impl DrawTarget for FrameBuffer {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(point) = to_screen(point) {
+                self.set_pixel(point, color.into());
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        fill_rows(area, colors, |rect, row| self.push_rect(rect, row));
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if let Some(rect) = clip(area) {
+            self.fill_rect(rect, color.into());
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        FrameBuffer::clear(self, color.into());
+        Ok(())
+    }
+}
+
+fn screen_size() -> Size {
+    Size::new(SCREEN_RECT.width as u32, SCREEN_RECT.height as u32)
+}
+
+/// Converts an `embedded-graphics` point, `None` if it is off-screen.
+fn to_screen(point: embedded_graphics_core::geometry::Point) -> Option<Point> {
+    let on_screen = (0..SCREEN_RECT.width as i32).contains(&point.x) && (0..SCREEN_RECT.height as i32).contains(&point.y);
+    on_screen.then_some(Point { x: point.x as u16, y: point.y as u16 })
+}
+
+/// Clips an `embedded-graphics` rectangle to the screen, `None` if nothing is left.
+fn clip(area: &Rectangle) -> Option<Rect> {
+    let visible = area.intersection(&Display.bounding_box());
+    let bottom_right = visible.bottom_right()?;
+
+    Some(Rect {
+        x: visible.top_left.x as u16,
+        y: visible.top_left.y as u16,
+        width: (bottom_right.x - visible.top_left.x + 1) as u16,
+        height: (bottom_right.y - visible.top_left.y + 1) as u16,
+    })
+}
+
+/// Reads `colors` row by row over `area` and passes the visible part of each row to `push`.
+fn fill_rows<I>(area: &Rectangle, colors: I, mut push: impl FnMut(Rect, &[Color]))
+where
+    I: IntoIterator<Item = Rgb565>,
+{
+    let Some(visible) = clip(area) else { return };
+    let mut colors = colors.into_iter();
+
+    let width = area.size.width as usize;
+    let skip_left = (visible.x as i32 - area.top_left.x) as usize;
+    let skip_top = (visible.y as i32 - area.top_left.y) as usize;
+
+    // Rows above the screen are consumed without being drawn
+    if skip_top > 0 && colors.nth(skip_top * width - 1).is_none() {
+        return;
+    }
+
+    let mut row = [crate::COLOR_BLACK; SCREEN_RECT.width as usize];
+    for y in visible.y..visible.y + visible.height {
+        let mut read = 0;
+        for (index, color) in colors.by_ref().take(width).enumerate() {
+            if index >= skip_left && index < skip_left + visible.width as usize {
+                row[index - skip_left] = color.into();
+            }
+            read += 1;
+        }
+        if read < width {
+            return;
+        }
+
+        push(Rect { x: visible.x, y, width: visible.width, height: 1 }, &row[..visible.width as usize]);
+    }
+}
+
+fn push_span(start: Point, span: &[Color]) {
+    if !span.is_empty() {
+        super::push_rect(Rect { x: start.x, y: start.y, width: span.len() as u16, height: 1 }, span);
+    }
+}
@@ -0,0 +1,325 @@
+//! # Host display backend
+//!
+//! A pure-Rust replacement for the `eadk_display_*` functions of Epsilon, enabled on host
+//! targets with the `host-display` feature. The screen is a 320x240 RGB565 buffer in memory
+//! (one per thread, so parallel tests don't share it), so any drawing code of the crate
+//! (`push_rect`, `draw::*`, `text`, `draw_text_box`...) runs in `cargo test` without the
+//! Epsilon simulator, and the result can be read back or saved as a PNG.
+//!
+//! `draw_string` uses a built-in 5x7 font centered in the cells of the firmware fonts
+//! ([`SMALL_FONT`], [`LARGE_FONT`]), so text sizes match the calculator but glyphs don't.
+//!
+//! ```rust,ignore
+//! use eadkp::{display, Point, Rect, COLOR_RED, COLOR_WHITE, COLOR_BLACK};
+//!
+//! display::host::clear(COLOR_WHITE);
+//! display::push_rect_uniform(Rect { x: 10, y: 10, width: 50, height: 20 }, COLOR_RED);
+//! display::draw_string("Hello", Point { x: 10, y: 40 }, false, COLOR_BLACK, COLOR_WHITE);
+//!
+//! assert_eq!(display::host::pixel(20, 20), Some(COLOR_RED));
+//! display::host::save_png("screen.png").unwrap();
+//! ```
+
+use core::ffi::{c_char, CStr};
+use std::cell::RefCell;
+use std::path::Path;
+
+use crate::{Color, Point, Rect, COLOR_BLACK, LARGE_FONT, SCREEN_RECT, SMALL_FONT};
+
+/// Width of the simulated screen in pixels
+pub const WIDTH: usize = SCREEN_RECT.width as usize;
+/// Height of the simulated screen in pixels
+pub const HEIGHT: usize = SCREEN_RECT.height as usize;
+
+thread_local! {
+    static SCREEN: RefCell<Box<[Color]>> = RefCell::new(vec![COLOR_BLACK; WIDTH * HEIGHT].into_boxed_slice());
+    static VBLANK_COUNT: RefCell<u64> = const { RefCell::new(0) };
+}
+
+// ==================================================================================
+// ==================================== ACCESS ======================================
+// ==================================================================================
+
+/// Fills the whole simulated screen with `color` (the screen starts black).
+pub fn clear(color: Color) {
+    SCREEN.with(|screen| screen.borrow_mut().fill(color));
+}
+
+/// Returns the color of the pixel `(x, y)`, `None` outside of the screen.
+pub fn pixel(x: u16, y: u16) -> Option<Color> {
+    if x >= SCREEN_RECT.width || y >= SCREEN_RECT.height {
+        return None;
+    }
+    SCREEN.with(|screen| Some(screen.borrow()[y as usize * WIDTH + x as usize]))
+}
+
+/// Calls `f` with the 320x240 pixels of the screen, row by row.
+pub fn with_pixels<R>(f: impl FnOnce(&[Color]) -> R) -> R {
+    SCREEN.with(|screen| f(&screen.borrow()))
+}
+
+/// Returns a copy of the 320x240 pixels of the screen, row by row.
+pub fn pixels() -> Vec<Color> {
+    with_pixels(|pixels| pixels.to_vec())
+}
+
+/// Number of calls to `wait_for_vblank` on this thread (i.e. frames drawn)
+pub fn vblank_count() -> u64 {
+    VBLANK_COUNT.with(|count| *count.borrow())
+}
+
+// ==================================================================================
+// ====================================== PNG =======================================
+// ==================================================================================
+
+/// Converts `pixels` (RGB565, row by row) to 8-bit RGB.
+pub fn to_rgb888(pixels: &[Color]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|color| {
+            let (r, g, b) = color.get_888();
+            [r, g, b]
+        })
+        .collect()
+}
+
+/// Encodes `pixels` (`width`x`height`, row by row) as an 8-bit RGB PNG file.
+pub fn encode_png(pixels: &[Color], width: u32, height: u32) -> std::io::Result<Vec<u8>> {
+    let mut file = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut file, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&to_rgb888(pixels))?;
+    writer.finish()?;
+
+    Ok(file)
+}
+
+/// Encodes the simulated screen as a PNG file.
+pub fn screen_png() -> Vec<u8> {
+    with_pixels(|pixels| encode_png(pixels, WIDTH as u32, HEIGHT as u32))
+        .expect("Encoding a 320x240 RGB image to memory can't fail")
+}
+
+/// Saves the simulated screen to a PNG file.
+pub fn save_png(path: impl AsRef<Path>) -> std::io::Result<()> {
+    std::fs::write(path, screen_png())
+}
+
+// ==================================================================================
+// =============================== EADK REPLACEMENTS ================================
+// ==================================================================================
+
+/// Part of `rect` inside the screen
+fn clip(rect: Rect) -> (usize, usize, usize, usize) {
+    let x = (rect.x as usize).min(WIDTH);
+    let y = (rect.y as usize).min(HEIGHT);
+    let width = (rect.width as usize).min(WIDTH - x);
+    let height = (rect.height as usize).min(HEIGHT - y);
+    (x, y, width, height)
+}
+
+pub(super) unsafe fn eadk_display_push_rect_uniform(rect: Rect, color: Color) {
+    let (x, y, width, height) = clip(rect);
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+        for row in y..y + height {
+            screen[row * WIDTH + x..row * WIDTH + x + width].fill(color);
+        }
+    });
+}
+
+pub(super) unsafe fn eadk_display_push_rect(rect: Rect, color: *const Color) {
+    // SAFETY: like Epsilon, `color` holds `rect.width * rect.height` pixels (see `display::push_rect`).
+    let pixels = unsafe { core::slice::from_raw_parts(color, rect.width as usize * rect.height as usize) };
+    let (x, y, width, height) = clip(rect);
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+        for row in 0..height {
+            let source = row * rect.width as usize;
+            let target = (y + row) * WIDTH + x;
+            screen[target..target + width].copy_from_slice(&pixels[source..source + width]);
+        }
+    });
+}
+
+pub(super) unsafe fn eadk_display_wait_for_vblank() {
+    VBLANK_COUNT.with(|count| *count.borrow_mut() += 1);
+}
+
+pub(super) unsafe fn eadk_display_pull_rect(rect: Rect, color: *mut Color) {
+    // SAFETY: `color` has room for `rect.width * rect.height` pixels (see `display::pull_rect`).
+    let pixels = unsafe { core::slice::from_raw_parts_mut(color, rect.width as usize * rect.height as usize) };
+    let (x, y, width, height) = clip(rect);
+    SCREEN.with(|screen| {
+        let screen = screen.borrow();
+        for row in 0..height {
+            let target = row * rect.width as usize;
+            let source = (y + row) * WIDTH + x;
+            pixels[target..target + width].copy_from_slice(&screen[source..source + width]);
+        }
+    });
+}
+
+pub(super) unsafe fn eadk_display_draw_string(
+    text: *const c_char,
+    point: Point,
+    large_font: bool,
+    text_color: Color,
+    background_color: Color,
+) {
+    // SAFETY: `text` comes from a `CString` (see `display::draw_string`).
+    let text = unsafe { CStr::from_ptr(text) }.to_string_lossy();
+    let (cell, scale) = if large_font { (LARGE_FONT, 2) } else { (SMALL_FONT, 1) };
+
+    // Glyph centered in the cell
+    let offset_x = (cell.width - GLYPH_WIDTH * scale) / 2;
+    let offset_y = (cell.height - GLYPH_HEIGHT * scale) / 2;
+
+    let mut cursor = point;
+    for character in text.chars() {
+        if character == '\n' {
+            cursor = Point { x: point.x, y: cursor.y.saturating_add(cell.height) };
+            continue;
+        }
+
+        let cell_rect = Rect { x: cursor.x, y: cursor.y, width: cell.width, height: cell.height };
+        unsafe { eadk_display_push_rect_uniform(cell_rect, background_color) };
+
+        let glyph = glyph(character);
+        for column in 0..GLYPH_WIDTH {
+            for row in 0..GLYPH_HEIGHT {
+                if glyph[column as usize] & (1 << row) != 0 {
+                    let dot = Rect {
+                        x: cursor.x.saturating_add(offset_x + column * scale),
+                        y: cursor.y.saturating_add(offset_y + row * scale),
+                        width: scale,
+                        height: scale,
+                    };
+                    unsafe { eadk_display_push_rect_uniform(dot, text_color) };
+                }
+            }
+        }
+
+        cursor.x = cursor.x.saturating_add(cell.width);
+    }
+}
+
+// ==================================================================================
+// ===================================== FONT =======================================
+// ==================================================================================
+
+const GLYPH_WIDTH: u16 = 5;
+const GLYPH_HEIGHT: u16 = 7;
+
+/// Returns the columns of the glyph of `character` (bit 0 is the top row), `?` for characters outside of ASCII.
+fn glyph(character: char) -> &'static [u8] {
+    let index = match character {
+        ' '..='~' => character as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    &FONT_5X7[index * GLYPH_WIDTH as usize..(index + 1) * GLYPH_WIDTH as usize]
+}
+
+/// Printable ASCII characters (`' '` to `'~'`), 5 columns per glyph
+#[rustfmt::skip]
+const FONT_5X7: [u8; 95 * 5] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, // ' '
+    0x00, 0x00, 0x5F, 0x00, 0x00, // !
+    0x00, 0x07, 0x00, 0x07, 0x00, // "
+    0x14, 0x7F, 0x14, 0x7F, 0x14, // #
+    0x24, 0x2A, 0x7F, 0x2A, 0x12, // $
+    0x23, 0x13, 0x08, 0x64, 0x62, // %
+    0x36, 0x49, 0x55, 0x22, 0x50, // &
+    0x00, 0x05, 0x03, 0x00, 0x00, // '
+    0x00, 0x1C, 0x22, 0x41, 0x00, // (
+    0x00, 0x41, 0x22, 0x1C, 0x00, // )
+    0x08, 0x2A, 0x1C, 0x2A, 0x08, // *
+    0x08, 0x08, 0x3E, 0x08, 0x08, // +
+    0x00, 0x50, 0x30, 0x00, 0x00, // ,
+    0x08, 0x08, 0x08, 0x08, 0x08, // -
+    0x00, 0x60, 0x60, 0x00, 0x00, // .
+    0x20, 0x10, 0x08, 0x04, 0x02, // /
+    0x3E, 0x51, 0x49, 0x45, 0x3E, // 0
+    0x00, 0x42, 0x7F, 0x40, 0x00, // 1
+    0x42, 0x61, 0x51, 0x49, 0x46, // 2
+    0x21, 0x41, 0x45, 0x4B, 0x31, // 3
+    0x18, 0x14, 0x12, 0x7F, 0x10, // 4
+    0x27, 0x45, 0x45, 0x45, 0x39, // 5
+    0x3C, 0x4A, 0x49, 0x49, 0x30, // 6
+    0x01, 0x71, 0x09, 0x05, 0x03, // 7
+    0x36, 0x49, 0x49, 0x49, 0x36, // 8
+    0x06, 0x49, 0x49, 0x29, 0x1E, // 9
+    0x00, 0x36, 0x36, 0x00, 0x00, // :
+    0x00, 0x56, 0x36, 0x00, 0x00, // ;
+    0x08, 0x14, 0x22, 0x41, 0x00, // <
+    0x14, 0x14, 0x14, 0x14, 0x14, // =
+    0x00, 0x41, 0x22, 0x14, 0x08, // >
+    0x02, 0x01, 0x51, 0x09, 0x06, // ?
+    0x32, 0x49, 0x79, 0x41, 0x3E, // @
+    0x7E, 0x11, 0x11, 0x11, 0x7E, // A
+    0x7F, 0x49, 0x49, 0x49, 0x36, // B
+    0x3E, 0x41, 0x41, 0x41, 0x22, // C
+    0x7F, 0x41, 0x41, 0x22, 0x1C, // D
+    0x7F, 0x49, 0x49, 0x49, 0x41, // E
+    0x7F, 0x09, 0x09, 0x09, 0x01, // F
+    0x3E, 0x41, 0x49, 0x49, 0x7A, // G
+    0x7F, 0x08, 0x08, 0x08, 0x7F, // H
+    0x00, 0x41, 0x7F, 0x41, 0x00, // I
+    0x20, 0x40, 0x41, 0x3F, 0x01, // J
+    0x7F, 0x08, 0x14, 0x22, 0x41, // K
+    0x7F, 0x40, 0x40, 0x40, 0x40, // L
+    0x7F, 0x02, 0x0C, 0x02, 0x7F, // M
+    0x7F, 0x04, 0x08, 0x10, 0x7F, // N
+    0x3E, 0x41, 0x41, 0x41, 0x3E, // O
+    0x7F, 0x09, 0x09, 0x09, 0x06, // P
+    0x3E, 0x41, 0x51, 0x21, 0x5E, // Q
+    0x7F, 0x09, 0x19, 0x29, 0x46, // R
+    0x46, 0x49, 0x49, 0x49, 0x31, // S
+    0x01, 0x01, 0x7F, 0x01, 0x01, // T
+    0x3F, 0x40, 0x40, 0x40, 0x3F, // U
+    0x1F, 0x20, 0x40, 0x20, 0x1F, // V
+    0x3F, 0x40, 0x38, 0x40, 0x3F, // W
+    0x63, 0x14, 0x08, 0x14, 0x63, // X
+    0x07, 0x08, 0x70, 0x08, 0x07, // Y
+    0x61, 0x51, 0x49, 0x45, 0x43, // Z
+    0x00, 0x7F, 0x41, 0x41, 0x00, // [
+    0x02, 0x04, 0x08, 0x10, 0x20, // \
+    0x00, 0x41, 0x41, 0x7F, 0x00, // ]
+    0x04, 0x02, 0x01, 0x02, 0x04, // ^
+    0x40, 0x40, 0x40, 0x40, 0x40, // _
+    0x00, 0x01, 0x02, 0x04, 0x00, // `
+    0x20, 0x54, 0x54, 0x54, 0x78, // a
+    0x7F, 0x48, 0x44, 0x44, 0x38, // b
+    0x38, 0x44, 0x44, 0x44, 0x20, // c
+    0x38, 0x44, 0x44, 0x48, 0x7F, // d
+    0x38, 0x54, 0x54, 0x54, 0x18, // e
+    0x08, 0x7E, 0x09, 0x01, 0x02, // f
+    0x0C, 0x52, 0x52, 0x52, 0x3E, // g
+    0x7F, 0x08, 0x04, 0x04, 0x78, // h
+    0x00, 0x44, 0x7D, 0x40, 0x00, // i
+    0x20, 0x40, 0x44, 0x3D, 0x00, // j
+    0x7F, 0x10, 0x28, 0x44, 0x00, // k
+    0x00, 0x41, 0x7F, 0x40, 0x00, // l
+    0x7C, 0x04, 0x18, 0x04, 0x78, // m
+    0x7C, 0x08, 0x04, 0x04, 0x78, // n
+    0x38, 0x44, 0x44, 0x44, 0x38, // o
+    0x7C, 0x14, 0x14, 0x14, 0x08, // p
+    0x08, 0x14, 0x14, 0x18, 0x7C, // q
+    0x7C, 0x08, 0x04, 0x04, 0x08, // r
+    0x48, 0x54, 0x54, 0x54, 0x20, // s
+    0x04, 0x3F, 0x44, 0x40, 0x20, // t
+    0x3C, 0x40, 0x40, 0x20, 0x7C, // u
+    0x1C, 0x20, 0x40, 0x20, 0x1C, // v
+    0x3C, 0x40, 0x30, 0x40, 0x3C, // w
+    0x44, 0x28, 0x10, 0x28, 0x44, // x
+    0x0C, 0x50, 0x50, 0x50, 0x3C, // y
+    0x44, 0x64, 0x54, 0x4C, 0x44, // z
+    0x00, 0x08, 0x36, 0x41, 0x00, // {
+    0x00, 0x00, 0x7F, 0x00, 0x00, // |
+    0x00, 0x41, 0x36, 0x08, 0x00, // }
+    0x08, 0x04, 0x08, 0x10, 0x08, // ~
+];
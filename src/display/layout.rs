@@ -0,0 +1,157 @@
+#[cfg(target_os = "none")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{Color, FontSize, Point, Rect, LARGE_FONT};
+
+/// Text used to mark a truncated text
+const ELLIPSIS: &str = "...";
+
+/// Horizontal alignment of a line of text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical alignment of a block of text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Alignment of a text in a box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Align {
+    pub horizontal: HorizontalAlign,
+    pub vertical: VerticalAlign,
+}
+
+impl Align {
+    pub const TOP_LEFT: Align = Align::new(HorizontalAlign::Left, VerticalAlign::Top);
+    pub const TOP_RIGHT: Align = Align::new(HorizontalAlign::Right, VerticalAlign::Top);
+    pub const CENTER: Align = Align::new(HorizontalAlign::Center, VerticalAlign::Middle);
+
+    pub const fn new(horizontal: HorizontalAlign, vertical: VerticalAlign) -> Self {
+        Align { horizontal, vertical }
+    }
+}
+
+/// Colors of a text box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextColors {
+    pub text: Color,
+    pub background: Color,
+}
+
+/// Returns the size `(width, height)` of a text drawn with `draw_string`.
+///
+/// The firmware fonts are monospaced: the width is the number of characters (not bytes) of the longest line.
+pub fn text_size(text: &str, font: FontSize) -> (u16, u16) {
+    let (columns, lines) = text
+        .split('\n')
+        .fold((0usize, 0usize), |(columns, lines), line| (columns.max(line.chars().count()), lines + 1));
+
+    (
+        (columns * font.width as usize).min(u16::MAX as usize) as u16,
+        (lines * font.height as usize).min(u16::MAX as usize) as u16,
+    )
+}
+
+/// Splits a text in lines of at most `max_width` pixels.
+///
+/// Lines are broken between words, a word longer than a line is broken between characters. Each `\n` starts a new line.
+pub fn wrap(text: &str, max_width: u16, font: FontSize) -> Vec<&str> {
+    let max_characters = (max_width / font.width.max(1)).max(1) as usize;
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut rest = paragraph.trim_end();
+        if rest.is_empty() {
+            lines.push(rest);
+            continue;
+        }
+
+        while !rest.is_empty() {
+            rest = rest.trim_start();
+
+            // Cut after the last space before the first character that does not fit, or at that character
+            let mut end = rest.len();
+            let mut last_space = None;
+            for (count, (index, character)) in rest.char_indices().enumerate() {
+                if count == max_characters {
+                    end = if character.is_whitespace() { index } else { last_space.unwrap_or(index) };
+                    break;
+                }
+                if character.is_whitespace() {
+                    last_space = Some(index);
+                }
+            }
+
+            lines.push(rest[..end].trim_end());
+            rest = &rest[end..];
+        }
+    }
+
+    lines
+}
+
+/// Draws a text in a box with `draw_string`, wrapped between words.
+///
+/// The box is filled with the background color, then the lines are aligned in it. If the text does not fit,
+/// the last visible line ends with `...`. Returns `true` if the text was truncated.
+pub fn draw_text_box(rect: Rect, text: &str, align: Align, font: FontSize, colors: TextColors) -> bool {
+    super::push_rect_uniform(rect, colors.background);
+
+    let max_lines = (rect.height / font.height.max(1)) as usize;
+    let max_characters = (rect.width / font.width.max(1)) as usize;
+    if max_lines == 0 || max_characters == 0 {
+        return !text.is_empty();
+    }
+
+    let lines = wrap(text, rect.width, font);
+    let truncated = lines.len() > max_lines;
+    let visible = &lines[..lines.len().min(max_lines)];
+
+    let block_height = visible.len() as u16 * font.height;
+    let mut y = rect.y + match align.vertical {
+        VerticalAlign::Top => 0,
+        VerticalAlign::Middle => (rect.height - block_height) / 2,
+        VerticalAlign::Bottom => rect.height - block_height,
+    };
+
+    for (index, &line) in visible.iter().enumerate() {
+        let ellipsized: String;
+        let line = if truncated && index == visible.len() - 1 {
+            ellipsized = ellipsize(line, max_characters);
+            ellipsized.as_str()
+        } else {
+            line
+        };
+
+        let width = line.chars().count() as u16 * font.width;
+        let x = rect.x + match align.horizontal {
+            HorizontalAlign::Left => 0,
+            HorizontalAlign::Center => (rect.width - width) / 2,
+            HorizontalAlign::Right => rect.width - width,
+        };
+
+        super::draw_string(line, Point { x, y }, font == LARGE_FONT, colors.text, colors.background);
+        y += font.height;
+    }
+
+    truncated
+}
+
+/// Appends `...` to a line, cutting it between characters only if it doesn't fit in `max_characters` with it.
+fn ellipsize(line: &str, max_characters: usize) -> String {
+    let mut result: String = if line.chars().count() + ELLIPSIS.len() <= max_characters {
+        line.into()
+    } else {
+        line.chars().take(max_characters.saturating_sub(ELLIPSIS.len())).collect()
+    };
+    result.push_str(&ELLIPSIS[..max_characters.min(ELLIPSIS.len())]);
+    result
+}
@@ -6,14 +6,30 @@ use std::boxed::Box;
 
 use super::*;
 
+mod eif2;
+mod image_ref;
+mod sprite;
+pub use image_ref::ImageRef;
+pub use eif2::{Compression, Eif2Image, EIF2_FLAG_ALPHA_MASK, EIF2_FLAG_COLOR_KEY, EIF2_HEADER_SIZE, EIF2_LZ_WINDOW, EIF2_VERSION};
+pub use sprite::{Rotation, Sprite, SpriteSheet, Transparency};
+
+#[cfg(target_os = "none")]
+use alloc::{vec, vec::Vec};
+
+/// Nombre de pixels envoyés à l'écran par bande lors d'un dessin en flux ([`ImageRef`], [`Eif2Image`])
+pub const IMAGE_SCRATCH_PIXELS: usize = 1024;
+
 /// # Représente une image chargée en mémoire
+///
+/// Pour dessiner une image sans la copier dans le tas, voir [`ImageRef`].
 /// 
 /// ## Champs
 /// - `magic_number`: Numéro magique identifiant le format de l'image
 /// - `width`: Largeur de l'image en pixels d'écran (axe Y)
 /// - `height`: Hauteur de l'image en pixels d'écran (axe X)
 /// - `pixels`: Données des pixels de l'image au format Color (RGB565)
-/// - `binary`: Pointeur vers les données binaires brutes de l'image dans la flash (Immuable/Statique)
+/// - `binary`: Pointeur vers les données binaires brutes de l'image dans la flash (Immuable/Statique).
+///   Pixels seuls pour EIF1, fichier complet (en-tête compris) pour EIF2.
 /// 
 /// # Remarques
 /// 
@@ -43,52 +59,168 @@ pub struct Image {
     pub binary: &'static [u8],
 }
 
+/// Taille de l'en-tête EIF1 en octets
+const EIF1_HEADER_SIZE: usize = 8;
+
+/// # Erreurs de lecture d'une image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageError {
+    /// Les données sont trop courtes pour contenir l'en-tête (ou la palette, ou le masque)
+    TooShort,
+    /// Le magic number ne correspond à aucun format d'image connu
+    BadMagic { found: u32 },
+    /// Révision du format non prise en charge
+    UnsupportedVersion { version: u8 },
+    /// Compression EIF2 inconnue
+    UnsupportedCompression { compression: u8 },
+    /// Nombre de bits par pixel EIF2 autre que 1, 2, 4, 8 ou 16
+    UnsupportedBpp { bits_per_pixel: u8 },
+    /// Pas assez de mémoire pour décoder les pixels de l'image
+    OutOfMemory { pixels: usize },
+    /// Le nombre de pixels disponibles ne correspond pas aux dimensions de l'en-tête
+    DimensionMismatch { expected: usize, found: usize },
+}
+
 impl Image {
+    /// Lit une image EIF1 ou EIF2 incluse avec `include_image!`. Retourne `None` si les données sont invalides.
     pub fn from_raw(binary_raw: &'static [u8]) -> Option<Self> {
-        
-        // Lire le magic number 
-        // let magic_number: u32 = unsafe { *(binary_raw.as_ptr() as *const u32) };
-        let magic_number: u32 = u32::from_le_bytes(
-            binary_raw[..4].try_into().expect("Slice trop courte pour magic number")
-        );
-
-        // EIF1 format
-        if magic_number == utils::EIF1_MAGIC_NUMBER {
-            return Some(Image::from_raw_eif1(binary_raw));
+        Image::try_from_static(binary_raw).ok()
+    }
+
+    /// Lit une image EIF1 ou EIF2 statique (incluse avec `include_image!`), en conservant `binary`.
+    pub fn try_from_static(binary_raw: &'static [u8]) -> Result<Self, ImageError> {
+        let image = Image::try_from_bytes(binary_raw)?;
+
+        let binary = if image.magic_number == utils::EIF1_MAGIC_NUMBER {
+            &binary_raw[EIF1_HEADER_SIZE..]
+        } else {
+            binary_raw
         };
-        
-        // Format inconnu
-        None
+
+        Ok(Image { binary, ..image })
     }
-    
+
+    /// # Lit une image EIF1 ou EIF2 depuis des octets quelconques
+    ///
+    /// Les données sont entièrement vérifiées : une image lue à l'exécution (ex: avec `storage::read()`)
+    /// ne peut pas faire planter l'application. Comme les octets ne sont pas statiques, `binary` est vide
+    /// (et [`Image::transparency_mask`] retourne `None`).
+    ///
+    /// ```rust,ignore
+    /// let bytes = eadkp::storage::read("photo.eif")?;
+    /// match eadkp::Image::try_from_bytes(&bytes) {
+    ///     Ok(image) => eadkp::display::push_image(&image, eadkp::Point { x: 0, y: 0 }),
+    ///     Err(error) => { /* Fichier corrompu */ }
+    /// }
+    /// ```
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, ImageError> {
+        let magic_number = bytes.get(..4).ok_or(ImageError::TooShort)?;
+        let magic_number = u32::from_le_bytes([magic_number[0], magic_number[1], magic_number[2], magic_number[3]]);
+
+        match magic_number {
+            utils::EIF1_MAGIC_NUMBER => Image::decode_eif1(bytes),
+            utils::EIF2_MAGIC_NUMBER => Image::decode_eif2(bytes),
+            found => Err(ImageError::BadMagic { found }),
+        }
+    }
+
+    /// Lit une image incluse avec `include_image!`.
+    ///
+    /// Le décodeur est choisi d'après le magic number : depuis que le builder écrit des images EIF2
+    /// par défaut, les appels existants `Image::from_raw_eif1(include_image!(..))` lisent aussi bien
+    /// les fichiers EIF1 que EIF2.
+    ///
+    /// # Panics
+    ///
+    /// Panique si les données ne sont pas une image EIF1 ou EIF2 valide, voir [`Image::try_from_bytes`] pour gérer l'erreur.
     pub fn from_raw_eif1(binary_raw: &'static [u8]) -> Self {
-        // Lire le magic number (déréférencer pour obtenir la valeur)
-        let magic_number: u32 = unsafe { *(binary_raw.as_ptr() as *const u32) };
+        Image::try_from_static(binary_raw).unwrap_or_else(|error| panic!("Image invalide : {:?}", error))
+    }
+
+    /// Décompresse une image EIF2 en mémoire. Retourne `None` si le fichier est invalide.
+    ///
+    /// Comme pour EIF1, les pixels transparents deviennent blancs : utiliser [`Image::transparency_mask`]
+    /// avec un [`Sprite`] pour les garder transparents, ou [`Eif2Image::draw`] pour dessiner sans copie.
+    pub fn from_raw_eif2(binary_raw: &'static [u8]) -> Option<Self> {
+        let image = Image::decode_eif2(binary_raw).ok()?;
+        Some(Image { binary: binary_raw, ..image })
+    }
+
+    fn decode_eif1(bytes: &[u8]) -> Result<Self, ImageError> {
+        let header = bytes.get(..EIF1_HEADER_SIZE).ok_or(ImageError::TooShort)?;
 
-        // Lire width et height (déréférencer pour obtenir les valeurs)
-        let width: u16 = unsafe { *(binary_raw.as_ptr().add(4) as *const u16) };
-        let height: u16 = unsafe { *(binary_raw.as_ptr().add(6) as *const u16) };
+        let magic_number = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        if magic_number != utils::EIF1_MAGIC_NUMBER {
+            return Err(ImageError::BadMagic { found: magic_number });
+        }
+
+        let width = u16::from_le_bytes([header[4], header[5]]);
+        let height = u16::from_le_bytes([header[6], header[7]]);
         let surface_size = (width as usize) * (height as usize);
 
-        // Obtenir un pointeur vers les données binaires de l'image
-        let binary: &'static [u8] = &binary_raw[8..];
+        // Vérifier que les données contiennent tous les pixels
+        let data = &bytes[EIF1_HEADER_SIZE..];
+        if data.len() / 2 < surface_size {
+            return Err(ImageError::DimensionMismatch { expected: surface_size, found: data.len() / 2 });
+        }
 
-        // Créer directement le Box<[Color]> avec un iterator
-        let pixels: Box<[Color]> = (0..surface_size)
-            .map(|i| {
-                let idx = i * 2;
-                let rgb565 = u16::from_le_bytes([binary[idx], binary[idx + 1]]);
-                Color { rgb565 }
-            })
+        let pixels: Box<[Color]> = data[..surface_size * 2]
+            .chunks_exact(2)
+            .map(|bytes| Color { rgb565: u16::from_le_bytes([bytes[0], bytes[1]]) })
             .collect();
 
-        Image {
-            magic_number,
-            width,
-            height,
+        Ok(Image { magic_number, width, height, pixels, binary: &[] })
+    }
+
+    fn decode_eif2(bytes: &[u8]) -> Result<Self, ImageError> {
+        let eif2 = Eif2Image::try_parse(bytes)?;
+
+        // Réserver la taille annoncée sans abandonner l'application si le tas est trop petit,
+        // et ne jamais décoder plus de pixels que prévu (données compressées malveillantes)
+        let surface_size = eif2.width as usize * eif2.height as usize;
+        let mut pixels: Vec<Color> = Vec::new();
+        pixels.try_reserve_exact(surface_size).map_err(|_| ImageError::OutOfMemory { pixels: surface_size })?;
+        pixels.extend(eif2.pixels().take(surface_size + 1));
+        if pixels.len() != surface_size {
+            return Err(ImageError::DimensionMismatch { expected: surface_size, found: pixels.len() });
+        }
+        let mut pixels = pixels.into_boxed_slice();
+
+        for (index, pixel) in pixels.iter_mut().enumerate() {
+            if !eif2.mask_opaque(index) || Some(*pixel) == eif2.color_key {
+                *pixel = COLOR_WHITE;
+            }
+        }
+
+        Ok(Image {
+            magic_number: utils::EIF2_MAGIC_NUMBER,
+            width: eif2.width,
+            height: eif2.height,
             pixels,
-            binary,
+            binary: &[],
+        })
+    }
+
+    /// Retourne le masque de transparence d'une image EIF2 (1 bit par pixel, `1` = opaque),
+    /// utilisable avec [`Sprite::with_mask`]. `None` si l'image n'a pas de transparence.
+    pub fn transparency_mask(&self) -> Option<Vec<u8>> {
+        if self.magic_number != utils::EIF2_MAGIC_NUMBER {
+            return None;
         }
+
+        let eif2 = Eif2Image::parse(self.binary)?;
+        if !eif2.has_transparency() {
+            return None;
+        }
+
+        let mut mask = vec![0u8; self.pixels.len().div_ceil(8)];
+        for (index, color) in eif2.pixels().enumerate() {
+            if eif2.mask_opaque(index) && Some(color) != eif2.color_key {
+                mask[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+
+        Some(mask)
     }
 
     pub fn get_pixels(&self) -> &[Color] {
@@ -0,0 +1,397 @@
+use super::{ImageError, IMAGE_SCRATCH_PIXELS};
+use crate::{display, utils, Color, Point, Rect, COLOR_BLACK, SCREEN_RECT};
+
+/// Taille de l'en-tête EIF2 en octets
+pub const EIF2_HEADER_SIZE: usize = 20;
+/// Révision du format EIF2 lue et écrite par cette version d'eadkp
+pub const EIF2_VERSION: u8 = 1;
+
+/// Taille de la fenêtre de la compression LZ (distance maximale d'une référence)
+pub const EIF2_LZ_WINDOW: usize = 4096;
+
+/// Drapeau : une couleur de transparence (color key) est définie
+pub const EIF2_FLAG_COLOR_KEY: u8 = 0b01;
+/// Drapeau : un masque alpha sur 1 bit par pixel suit les données
+pub const EIF2_FLAG_ALPHA_MASK: u8 = 0b10;
+
+/// # Compression des pixels d'une image EIF2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Pixels bruts
+    None = 0,
+    /// Répétitions de pixels (RLE, type PackBits)
+    Rle = 1,
+    /// Références aux octets précédents (LZSS, fenêtre de 4 Ko)
+    Lz = 2,
+}
+
+/// # Image EIF2 lue en place dans la flash
+///
+/// Les pixels sont décompressés à la volée : [`Eif2Image::draw`] les envoie
+/// directement à l'écran par bandes de lignes, sans allocation (tampon et fenêtre LZ sur la pile).
+///
+/// Voir `docs/TECHNICALS/EIF/EIF2.md` pour la structure du fichier.
+#[derive(Clone, Copy, Debug)]
+pub struct Eif2Image<'a> {
+    pub width: u16,
+    pub height: u16,
+    pub compression: Compression,
+    /// Bits par pixel : 16 (RGB565 direct) ou 1, 2, 4, 8 (palette)
+    pub bits_per_pixel: u8,
+    /// Couleur des pixels transparents, si l'image utilise une color key
+    pub color_key: Option<Color>,
+    palette: &'a [u8],
+    data: &'a [u8],
+    mask: Option<&'a [u8]>,
+}
+
+impl<'a> Eif2Image<'a> {
+    /// Lit l'en-tête d'une image EIF2. Retourne `None` si le fichier est invalide ou tronqué.
+    pub fn parse(raw: &'a [u8]) -> Option<Self> {
+        Eif2Image::try_parse(raw).ok()
+    }
+
+    /// Lit l'en-tête d'une image EIF2 et vérifie que la palette, les pixels et le masque sont présents.
+    ///
+    /// Le flux compressé est décodé une fois : un flux qui s'arrête avant `largeur × hauteur` pixels
+    /// est refusé avec [`ImageError::DimensionMismatch`].
+    pub fn try_parse(raw: &'a [u8]) -> Result<Self, ImageError> {
+        let header = raw.get(..EIF2_HEADER_SIZE).ok_or(ImageError::TooShort)?;
+        let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+
+        let magic_number = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        if magic_number != utils::EIF2_MAGIC_NUMBER {
+            return Err(ImageError::BadMagic { found: magic_number });
+        }
+
+        let version = header[4];
+        if version != EIF2_VERSION {
+            return Err(ImageError::UnsupportedVersion { version });
+        }
+        let compression = match header[5] {
+            0 => Compression::None,
+            1 => Compression::Rle,
+            2 => Compression::Lz,
+            compression => return Err(ImageError::UnsupportedCompression { compression }),
+        };
+        let bits_per_pixel = header[6];
+        if !matches!(bits_per_pixel, 1 | 2 | 4 | 8 | 16) {
+            return Err(ImageError::UnsupportedBpp { bits_per_pixel });
+        }
+
+        let flags = header[7];
+        let (width, height) = (u16_at(8), u16_at(10));
+        let palette_length = u16_at(12) as usize * 2;
+        let data_length = u32::from_le_bytes([header[16], header[17], header[18], header[19]]) as usize;
+
+        let palette_end = EIF2_HEADER_SIZE + palette_length;
+        let data_end = palette_end.checked_add(data_length).ok_or(ImageError::TooShort)?;
+        let surface_size = width as usize * height as usize;
+        let mask = if flags & EIF2_FLAG_ALPHA_MASK != 0 {
+            let mask_length = surface_size.div_ceil(8);
+            let mask_end = data_end.checked_add(mask_length).ok_or(ImageError::TooShort)?;
+            Some(raw.get(data_end..mask_end).ok_or(ImageError::TooShort)?)
+        } else {
+            None
+        };
+
+        let image = Eif2Image {
+            width,
+            height,
+            compression,
+            bits_per_pixel,
+            color_key: (flags & EIF2_FLAG_COLOR_KEY != 0).then_some(Color { rgb565: u16_at(14) }),
+            palette: raw.get(EIF2_HEADER_SIZE..palette_end).ok_or(ImageError::TooShort)?,
+            data: raw.get(palette_end..data_end).ok_or(ImageError::TooShort)?,
+            mask,
+        };
+
+        // Décompresser une fois (sans rien garder) pour vérifier que le flux contient tous les pixels
+        let found = image.pixels().count();
+        if found != surface_size {
+            return Err(ImageError::DimensionMismatch { expected: surface_size, found });
+        }
+
+        Ok(image)
+    }
+
+    /// Retourne `true` si l'image a des pixels transparents (color key ou masque).
+    pub fn has_transparency(&self) -> bool {
+        self.color_key.is_some() || self.mask.is_some()
+    }
+
+    /// Itérateur décompressant les pixels ligne par ligne.
+    ///
+    /// Les pixels transparents gardent la couleur stockée (la color key s'il y en a une).
+    pub fn pixels(&self) -> Pixels<'a> {
+        Pixels {
+            bytes: Bytes::new(self.data, self.compression, if self.bits_per_pixel == 16 { 2 } else { 1 }),
+            bits_per_pixel: self.bits_per_pixel,
+            palette: self.palette,
+            current: 0,
+            bits_left: 0,
+            remaining: self.width as usize * self.height as usize,
+        }
+    }
+
+    /// Retourne `true` si le pixel numéro `index` (ligne par ligne) est opaque, d'après le masque alpha.
+    ///
+    /// Les images sans masque sont entièrement opaques (la color key se vérifie sur la couleur).
+    pub fn mask_opaque(&self, index: usize) -> bool {
+        self.mask.is_none_or(|mask| mask.get(index / 8).is_some_and(|byte| byte & (0x80 >> (index % 8)) != 0))
+    }
+
+    /// Décompresse l'image et l'envoie à l'écran, coin haut-gauche en `point`.
+    ///
+    /// Les images opaques sont envoyées par bandes de lignes, les autres par suites de pixels opaques.
+    /// Les pixels hors de l'écran sont ignorés, et le dessin s'arrête à la dernière ligne complète
+    /// si le flux se termine trop tôt.
+    pub fn draw(&self, point: Point) {
+        let width = self.width as usize;
+        let visible_width = self.width.min(SCREEN_RECT.width.saturating_sub(point.x)) as usize;
+        let visible_height = self.height.min(SCREEN_RECT.height.saturating_sub(point.y));
+        if visible_width == 0 {
+            return;
+        }
+
+        // Bandes de lignes dans un tampon sur la pile (une ligne visible fait au plus 320 pixels)
+        let rows_per_band = IMAGE_SCRATCH_PIXELS / visible_width;
+        let mut band = [COLOR_BLACK; IMAGE_SCRATCH_PIXELS];
+        let mut band_length = 0;
+        let mut pixels = self.pixels();
+        let mut band_start = 0u16;
+
+        for y in 0..visible_height {
+            let row = &mut band[band_length..band_length + visible_width];
+            let mut decoded = 0;
+            for (x, color) in pixels.by_ref().take(width).enumerate() {
+                decoded += 1;
+                if x < visible_width {
+                    row[x] = color;
+                }
+            }
+
+            // Flux terminé avant la fin de la ligne : n'envoyer que les lignes complètes
+            if decoded < width {
+                if !self.has_transparency() && y > band_start {
+                    display::push_rect(
+                        Rect { x: point.x, y: point.y + band_start, width: visible_width as u16, height: y - band_start },
+                        &band[..band_length],
+                    );
+                }
+                return;
+            }
+
+            if self.has_transparency() {
+                self.push_opaque_spans(&band[..visible_width], point.x, point.y + y, y as usize * width);
+                continue;
+            }
+            band_length += visible_width;
+
+            // Envoyer la bande quand elle est pleine ou à la dernière ligne visible
+            if (y - band_start + 1) as usize == rows_per_band || y + 1 == visible_height {
+                display::push_rect(
+                    Rect { x: point.x, y: point.y + band_start, width: visible_width as u16, height: y - band_start + 1 },
+                    &band[..band_length],
+                );
+                band_length = 0;
+                band_start = y + 1;
+            }
+        }
+    }
+
+    /// Envoie les suites de pixels opaques d'une ligne.
+    fn push_opaque_spans(&self, row: &[Color], x: u16, y: u16, first_index: usize) {
+        let opaque = |index: usize| {
+            self.mask_opaque(first_index + index) && self.color_key.is_none_or(|key| row[index] != key)
+        };
+
+        let mut start = 0;
+        while start < row.len() {
+            if !opaque(start) {
+                start += 1;
+                continue;
+            }
+
+            let mut end = start;
+            while end < row.len() && opaque(end) {
+                end += 1;
+            }
+
+            display::push_rect(Rect { x: x + start as u16, y, width: (end - start) as u16, height: 1 }, &row[start..end]);
+            start = end;
+        }
+    }
+}
+
+/// # Pixels décompressés d'une [`Eif2Image`]
+pub struct Pixels<'a> {
+    bytes: Bytes<'a>,
+    bits_per_pixel: u8,
+    palette: &'a [u8],
+    /// Octet en cours de lecture (images à palette)
+    current: u8,
+    bits_left: u8,
+    remaining: usize,
+}
+
+impl Iterator for Pixels<'_> {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        if self.bits_per_pixel == 16 {
+            let low = self.bytes.next()?;
+            let high = self.bytes.next()?;
+            return Some(Color { rgb565: u16::from_le_bytes([low, high]) });
+        }
+
+        // Index dans la palette, bits de poids fort en premier
+        if self.bits_left == 0 {
+            self.current = self.bytes.next()?;
+            self.bits_left = 8;
+        }
+        self.bits_left -= self.bits_per_pixel;
+        let index = ((self.current >> self.bits_left) & ((1u16 << self.bits_per_pixel) - 1) as u8) as usize;
+
+        let rgb565 = match self.palette.get(index * 2..index * 2 + 2) {
+            Some(entry) => u16::from_le_bytes([entry[0], entry[1]]),
+            None => 0,
+        };
+        Some(Color { rgb565 })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// Flux d'octets décompressés
+///
+/// La fenêtre LZ reste dans la variante pour que le décodage ne touche pas au tas.
+#[allow(clippy::large_enum_variant)]
+enum Bytes<'a> {
+    Raw { data: &'a [u8], position: usize },
+    Rle {
+        data: &'a [u8],
+        position: usize,
+        /// Taille d'une unité répétée (2 octets pour le RGB565, 1 pour les palettes)
+        unit: usize,
+        /// Octets restant à copier tels quels
+        literal: usize,
+        /// Octets restant à produire en répétant `value`
+        repeat: usize,
+        value: [u8; 2],
+        index: usize,
+    },
+    Lz {
+        data: &'a [u8],
+        position: usize,
+        flags: u8,
+        flags_left: u8,
+        /// Distance et longueur restante de la référence en cours
+        distance: usize,
+        length: usize,
+        window: [u8; EIF2_LZ_WINDOW],
+        window_position: usize,
+    },
+}
+
+impl<'a> Bytes<'a> {
+    fn new(data: &'a [u8], compression: Compression, unit: usize) -> Self {
+        match compression {
+            Compression::None => Bytes::Raw { data, position: 0 },
+            Compression::Rle => Bytes::Rle { data, position: 0, unit, literal: 0, repeat: 0, value: [0; 2], index: 0 },
+            Compression::Lz => Bytes::Lz {
+                data,
+                position: 0,
+                flags: 0,
+                flags_left: 0,
+                distance: 0,
+                length: 0,
+                window: [0; EIF2_LZ_WINDOW],
+                window_position: 0,
+            },
+        }
+    }
+}
+
+impl Iterator for Bytes<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        match self {
+            Bytes::Raw { data, position } => {
+                let byte = *data.get(*position)?;
+                *position += 1;
+                Some(byte)
+            }
+
+            Bytes::Rle { data, position, unit, literal, repeat, value, index } => loop {
+                if *literal > 0 {
+                    *literal -= 1;
+                    let byte = *data.get(*position)?;
+                    *position += 1;
+                    return Some(byte);
+                }
+                if *repeat > 0 {
+                    *repeat -= 1;
+                    let byte = value[*index % *unit];
+                    *index += 1;
+                    return Some(byte);
+                }
+
+                // Octet de contrôle : < 128 => (c + 1) unités brutes, sinon (c - 126) répétitions d'une unité
+                let control = *data.get(*position)? as usize;
+                *position += 1;
+                if control < 128 {
+                    *literal = (control + 1) * *unit;
+                } else {
+                    let bytes = data.get(*position..*position + *unit)?;
+                    value[..*unit].copy_from_slice(bytes);
+                    *position += *unit;
+                    *repeat = (control - 126) * *unit;
+                    *index = 0;
+                }
+            },
+
+            Bytes::Lz { data, position, flags, flags_left, distance, length, window, window_position } => {
+                if *length == 0 {
+                    // Un octet de drapeaux pour 8 éléments : 1 = octet brut, 0 = référence
+                    if *flags_left == 0 {
+                        *flags = *data.get(*position)?;
+                        *position += 1;
+                        *flags_left = 8;
+                    }
+                    let literal = *flags & 1 != 0;
+                    *flags >>= 1;
+                    *flags_left -= 1;
+
+                    if literal {
+                        let byte = *data.get(*position)?;
+                        *position += 1;
+                        window[*window_position] = byte;
+                        *window_position = (*window_position + 1) % EIF2_LZ_WINDOW;
+                        return Some(byte);
+                    }
+
+                    // Référence sur 2 octets : distance sur 12 bits (1-4096), longueur sur 4 bits (3-18)
+                    let reference = data.get(*position..*position + 2)?;
+                    *position += 2;
+                    *distance = (((reference[0] as usize) << 4) | (reference[1] as usize >> 4)) + 1;
+                    *length = (reference[1] as usize & 0x0F) + 3;
+                }
+
+                *length -= 1;
+                let byte = window[(*window_position + EIF2_LZ_WINDOW - *distance) % EIF2_LZ_WINDOW];
+                window[*window_position] = byte;
+                *window_position = (*window_position + 1) % EIF2_LZ_WINDOW;
+                Some(byte)
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Eif2Image, Image, ImageError, IMAGE_SCRATCH_PIXELS};
+use crate::{display, utils, Color, Point, Rect, COLOR_BLACK, SCREEN_RECT};
+
+/// Format des pixels d'une [`ImageRef`]
+#[derive(Clone, Copy, Debug)]
+enum Source<'a> {
+    /// Pixels RGB565 bruts (sans l'en-tête)
+    Eif1(&'a [u8]),
+    Eif2(Eif2Image<'a>),
+}
+
+/// # Image lue directement dans la flash, sans copie en RAM
+///
+/// Deux façons d'afficher une image incluse avec `include_image!` :
+///
+/// - **Charger en RAM** avec [`Image::from_raw`] (ou [`ImageRef::load`]) : les pixels sont décodés
+///   une seule fois dans le tas. Le dessin est rapide et les pixels sont accessibles
+///   (sprites, transformations), mais l'image coûte `largeur × hauteur × 2` octets de tas.
+/// - **Lire en flux** avec [`ImageRef`] : rien n'est alloué, les pixels sont lus dans la flash
+///   à chaque dessin. C'est le seul choix possible pour les grandes images (fonds d'écran)
+///   avec le petit tas de la calculatrice.
+///
+/// Les pixels EIF1 alignés sont envoyés sans aucune copie, sinon ils passent par un tampon
+/// de [`IMAGE_SCRATCH_PIXELS`] pixels, bande de lignes par bande de lignes. Les images EIF2
+/// sont décompressées à la volée (voir [`Eif2Image::draw`]).
+///
+/// ```rust,ignore
+/// static BACKGROUND: &[u8] = include_image!("background.png");
+///
+/// let background = eadkp::ImageRef::from_raw(BACKGROUND).unwrap();
+/// background.draw(eadkp::Point { x: 0, y: 0 });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ImageRef<'a> {
+    pub width: u16,
+    pub height: u16,
+    raw: &'a [u8],
+    source: Source<'a>,
+}
+
+impl<'a> ImageRef<'a> {
+    /// Lit l'en-tête d'une image EIF1 ou EIF2. Retourne `None` si le format est inconnu ou le fichier tronqué.
+    pub fn from_raw(raw: &'a [u8]) -> Option<Self> {
+        ImageRef::try_from_bytes(raw).ok()
+    }
+
+    /// Lit l'en-tête d'une image EIF1 ou EIF2 et vérifie que tous les pixels sont présents.
+    pub fn try_from_bytes(raw: &'a [u8]) -> Result<Self, ImageError> {
+        let magic_number = raw.get(..4).ok_or(ImageError::TooShort)?;
+        let magic_number = u32::from_le_bytes([magic_number[0], magic_number[1], magic_number[2], magic_number[3]]);
+
+        match magic_number {
+            utils::EIF1_MAGIC_NUMBER => {
+                let header = raw.get(..8).ok_or(ImageError::TooShort)?;
+                let width = u16::from_le_bytes([header[4], header[5]]);
+                let height = u16::from_le_bytes([header[6], header[7]]);
+
+                // Comparaison sans multiplication par 2 : pas de débordement sur 32 bits
+                let expected = width as usize * height as usize;
+                let found = (raw.len() - 8) / 2;
+                if found < expected {
+                    return Err(ImageError::DimensionMismatch { expected, found });
+                }
+                Ok(ImageRef { width, height, raw, source: Source::Eif1(&raw[8..8 + expected * 2]) })
+            }
+            utils::EIF2_MAGIC_NUMBER => {
+                let eif2 = Eif2Image::try_parse(raw)?;
+                Ok(ImageRef { width: eif2.width, height: eif2.height, raw, source: Source::Eif2(eif2) })
+            }
+            found => Err(ImageError::BadMagic { found }),
+        }
+    }
+
+    /// Retourne le rectangle occupé par l'image avec son coin haut-gauche en `(x, y)`.
+    pub fn for_coordinates(&self, x: u16, y: u16) -> Rect {
+        Rect { x, y, width: self.width, height: self.height }
+    }
+
+    /// Dessine l'image avec son coin haut-gauche en `point`, sans allocation.
+    ///
+    /// Les pixels hors de l'écran sont ignorés.
+    pub fn draw(&self, point: Point) {
+        match self.source {
+            Source::Eif1(pixels) => self.draw_eif1(pixels, point),
+            Source::Eif2(eif2) => eif2.draw(point),
+        }
+    }
+
+    fn draw_eif1(&self, pixels: &[u8], point: Point) {
+        let width = self.width as usize;
+        let visible_width = self.width.min(SCREEN_RECT.width.saturating_sub(point.x)) as usize;
+        let visible_height = self.height.min(SCREEN_RECT.height.saturating_sub(point.y)) as usize;
+        if visible_width == 0 || visible_height == 0 {
+            return;
+        }
+
+        // Sans copie : les octets sont déjà des `Color` (little-endian) si l'adresse est alignée
+        #[cfg(target_endian = "little")]
+        {
+            // SAFETY: `Color` est un `u16` transparent, valide pour toute valeur de 2 octets.
+            let (prefix, colors, _) = unsafe { pixels.align_to::<Color>() };
+            if prefix.is_empty() && colors.len() >= width * visible_height {
+                if visible_width == width {
+                    display::push_rect(
+                        Rect { x: point.x, y: point.y, width: self.width, height: visible_height as u16 },
+                        &colors[..width * visible_height],
+                    );
+                    return;
+                }
+
+                for y in 0..visible_height {
+                    display::push_rect(
+                        Rect { x: point.x, y: point.y + y as u16, width: visible_width as u16, height: 1 },
+                        &colors[y * width..y * width + visible_width],
+                    );
+                }
+                return;
+            }
+        }
+
+        // Sinon, copie par bandes de lignes dans un tampon sur la pile (une ligne visible fait au plus 320 pixels)
+        let mut scratch = [COLOR_BLACK; IMAGE_SCRATCH_PIXELS];
+        let rows_per_band = IMAGE_SCRATCH_PIXELS / visible_width;
+
+        for band_start in (0..visible_height).step_by(rows_per_band) {
+            let rows = rows_per_band.min(visible_height - band_start);
+
+            for row in 0..rows {
+                let line = (band_start + row) * width * 2;
+                for x in 0..visible_width {
+                    let index = line + x * 2;
+                    scratch[row * visible_width + x] = Color { rgb565: u16::from_le_bytes([pixels[index], pixels[index + 1]]) };
+                }
+            }
+
+            display::push_rect(
+                Rect { x: point.x, y: point.y + band_start as u16, width: visible_width as u16, height: rows as u16 },
+                &scratch[..rows * visible_width],
+            );
+        }
+    }
+}
+
+impl ImageRef<'static> {
+    /// Charge l'image en RAM (décodage complet dans le tas), voir [`Image::from_raw`].
+    pub fn load(&self) -> Option<Image> {
+        Image::from_raw(self.raw)
+    }
+}
@@ -0,0 +1,241 @@
+#[cfg(target_os = "none")]
+use alloc::vec::Vec;
+
+use crate::{display, Color, Image, Point, Rect, SCREEN_RECT};
+
+/// # Transparence d'un sprite
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transparency<'a> {
+    /// Tous les pixels sont dessinés
+    Opaque,
+    /// Les pixels de cette couleur ne sont pas dessinés (ex: `COLOR_WHITE` pour les zones transparentes des PNG)
+    ColorKey(Color),
+    /// Masque sur 1 bit par pixel de l'image entière, ligne par ligne, bit de poids fort en premier (`1` = opaque)
+    Mask(&'a [u8]),
+}
+
+/// # Rotation d'un sprite, dans le sens des aiguilles d'une montre
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    None,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// # Sprite : une zone d'une [`Image`] dessinée avec transparence
+///
+/// Le sprite emprunte les pixels de l'image, rien n'est copié. Il peut être retourné
+/// horizontalement ou verticalement (appliqué en premier) puis tourné par pas de 90°.
+///
+/// Seules les suites de pixels opaques sont envoyées à l'écran : le fond reste visible.
+///
+/// ```rust,ignore
+/// let sheet_image = eadkp::Image::from_raw(include_image!("player.png")).unwrap();
+/// let sheet = eadkp::SpriteSheet::new(&sheet_image, 16, 16);
+///
+/// let frame = sheet.frame(3).unwrap()
+///     .with_color_key(eadkp::COLOR_WHITE)
+///     .flipped(true, false);
+/// frame.draw(eadkp::Point { x: 100, y: 80 });
+/// ```
+#[derive(Clone, Copy)]
+pub struct Sprite<'a> {
+    image: &'a Image,
+    /// Zone de l'image utilisée
+    source: Rect,
+    transparency: Transparency<'a>,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    rotation: Rotation,
+}
+
+impl<'a> Sprite<'a> {
+    /// Crée un sprite opaque couvrant toute l'image.
+    pub fn new(image: &'a Image) -> Self {
+        Sprite {
+            image,
+            source: Rect { x: 0, y: 0, width: image.width, height: image.height },
+            transparency: Transparency::Opaque,
+            flip_horizontal: false,
+            flip_vertical: false,
+            rotation: Rotation::None,
+        }
+    }
+
+    /// Crée un sprite sur une zone de l'image. Retourne `None` si la zone dépasse de l'image.
+    pub fn from_region(image: &'a Image, source: Rect) -> Option<Self> {
+        let inside = source.x as u32 + source.width as u32 <= image.width as u32
+            && source.y as u32 + source.height as u32 <= image.height as u32;
+
+        inside.then_some(Sprite { source, ..Sprite::new(image) })
+    }
+
+    /// Ne dessine pas les pixels de la couleur `key`.
+    pub fn with_color_key(self, key: Color) -> Self {
+        Sprite { transparency: Transparency::ColorKey(key), ..self }
+    }
+
+    /// Utilise un masque de transparence (voir [`Transparency::Mask`]).
+    pub fn with_mask(self, mask: &'a [u8]) -> Self {
+        Sprite { transparency: Transparency::Mask(mask), ..self }
+    }
+
+    /// Retourne le sprite horizontalement et/ou verticalement.
+    pub fn flipped(self, horizontal: bool, vertical: bool) -> Self {
+        Sprite { flip_horizontal: horizontal, flip_vertical: vertical, ..self }
+    }
+
+    /// Tourne le sprite par pas de 90°.
+    pub fn rotated(self, rotation: Rotation) -> Self {
+        Sprite { rotation, ..self }
+    }
+
+    /// Largeur du sprite à l'écran (après rotation)
+    pub fn width(&self) -> u16 {
+        match self.rotation {
+            Rotation::None | Rotation::Rotate180 => self.source.width,
+            Rotation::Rotate90 | Rotation::Rotate270 => self.source.height,
+        }
+    }
+
+    /// Hauteur du sprite à l'écran (après rotation)
+    pub fn height(&self) -> u16 {
+        match self.rotation {
+            Rotation::None | Rotation::Rotate180 => self.source.height,
+            Rotation::Rotate90 | Rotation::Rotate270 => self.source.width,
+        }
+    }
+
+    /// Retourne la couleur du pixel `(x, y)` du sprite (après retournement et rotation), `None` s'il est transparent
+    /// ou hors du sprite.
+    pub fn pixel(&self, x: u16, y: u16) -> Option<Color> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let (width, height) = (self.source.width, self.source.height);
+
+        // Coordonnées dans la zone source, avant rotation
+        let (mut u, mut v) = match self.rotation {
+            Rotation::None => (x, y),
+            Rotation::Rotate90 => (y, height - 1 - x),
+            Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+            Rotation::Rotate270 => (width - 1 - y, x),
+        };
+        if self.flip_horizontal {
+            u = width - 1 - u;
+        }
+        if self.flip_vertical {
+            v = height - 1 - v;
+        }
+
+        let index = (self.source.y + v) as usize * self.image.width as usize + (self.source.x + u) as usize;
+        let color = *self.image.pixels.get(index)?;
+
+        let opaque = match self.transparency {
+            Transparency::Opaque => true,
+            Transparency::ColorKey(key) => color != key,
+            Transparency::Mask(mask) => mask.get(index / 8).is_some_and(|byte| byte & (0x80 >> (index % 8)) != 0),
+        };
+
+        opaque.then_some(color)
+    }
+
+    /// Dessine le sprite avec son coin haut-gauche en `point`.
+    ///
+    /// Les pixels hors de l'écran sont ignorés, chaque suite de pixels opaques d'une ligne est envoyée avec `push_rect`.
+    pub fn draw(&self, point: Point) {
+        let visible_width = self.width().min(SCREEN_RECT.width.saturating_sub(point.x));
+        let visible_height = self.height().min(SCREEN_RECT.height.saturating_sub(point.y));
+        let mut span: Vec<Color> = Vec::with_capacity(visible_width as usize);
+
+        for y in 0..visible_height {
+            let mut x = 0;
+            while x < visible_width {
+                // Rassembler la prochaine suite de pixels opaques
+                span.clear();
+                let start = x;
+                while x < visible_width {
+                    match self.pixel(x, y) {
+                        Some(color) => span.push(color),
+                        None => break,
+                    }
+                    x += 1;
+                }
+
+                if !span.is_empty() {
+                    display::push_rect(
+                        Rect { x: point.x + start, y: point.y + y, width: span.len() as u16, height: 1 },
+                        &span,
+                    );
+                }
+                x += 1;
+            }
+        }
+    }
+}
+
+/// # Planche de sprites découpée en grille
+///
+/// Les images sont numérotées ligne par ligne, de gauche à droite.
+#[derive(Clone, Copy)]
+pub struct SpriteSheet<'a> {
+    image: &'a Image,
+    frame_width: u16,
+    frame_height: u16,
+}
+
+impl<'a> SpriteSheet<'a> {
+    /// Découpe `image` en cases de `frame_width`x`frame_height` pixels.
+    pub fn new(image: &'a Image, frame_width: u16, frame_height: u16) -> Self {
+        SpriteSheet { image, frame_width: frame_width.max(1), frame_height: frame_height.max(1) }
+    }
+
+    /// Nombre de colonnes de la grille
+    pub fn columns(&self) -> u16 {
+        self.image.width / self.frame_width
+    }
+
+    /// Nombre de lignes de la grille
+    pub fn rows(&self) -> u16 {
+        self.image.height / self.frame_height
+    }
+
+    /// Nombre total d'images de la planche
+    pub fn len(&self) -> usize {
+        self.columns() as usize * self.rows() as usize
+    }
+
+    /// Retourne `true` si l'image est plus petite qu'une case.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Retourne l'image numéro `index`, `None` si elle est hors de la planche.
+    pub fn frame(&self, index: usize) -> Option<Sprite<'a>> {
+        let columns = self.columns() as usize;
+        if index >= self.len() {
+            return None;
+        }
+
+        self.frame_at((index % columns) as u16, (index / columns) as u16)
+    }
+
+    /// Retourne l'image de la case `(column, row)`, `None` si elle est hors de la planche.
+    pub fn frame_at(&self, column: u16, row: u16) -> Option<Sprite<'a>> {
+        if column >= self.columns() || row >= self.rows() {
+            return None;
+        }
+
+        Sprite::from_region(
+            self.image,
+            Rect {
+                x: column * self.frame_width,
+                y: row * self.frame_height,
+                width: self.frame_width,
+                height: self.frame_height,
+            },
+        )
+    }
+}
@@ -1,6 +1,12 @@
 
 use enum_iterator::Sequence;
 
+#[cfg(not(target_os = "none"))]
+use std::cell::RefCell;
+
+#[cfg(not(target_os = "none"))]
+pub mod replay;
+
 type EadkKeyboardState = u64;
 
 #[allow(dead_code)]
@@ -55,11 +61,18 @@ pub enum Key {
     Exe = 52,
 }
 
+#[cfg(not(all(not(target_os = "none"), feature = "host-input")))]
 unsafe extern "C" {
     fn eadk_keyboard_scan() -> EadkKeyboardState;
 }
 
-#[derive(Clone, Copy)]
+/// Without Epsilon (feature `host-input`): no key is pressed until a source is installed
+#[cfg(all(not(target_os = "none"), feature = "host-input"))]
+unsafe fn eadk_keyboard_scan() -> EadkKeyboardState {
+    0
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct KeyboardState(EadkKeyboardState);
 
 impl Default for KeyboardState {
@@ -70,6 +83,11 @@ impl Default for KeyboardState {
 
 impl KeyboardState {
     pub fn scan() -> Self {
+        #[cfg(not(target_os = "none"))]
+        if let Some(state) = with_source(|source| source.scan()) {
+            return state;
+        }
+
         Self::from_raw(unsafe { eadk_keyboard_scan() })
     }
 
@@ -81,6 +99,11 @@ impl KeyboardState {
         Self(state)
     }
 
+    /// Bitmask of the pressed keys (bit `key as u8`)
+    pub fn raw(&self) -> EadkKeyboardState {
+        self.0
+    }
+
     pub fn key_down(&self, key: Key) -> bool {
         (self.0 >> (key as u8)) & 1 != 0
     }
@@ -95,7 +118,7 @@ impl KeyboardState {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Sequence, Debug)]
 #[repr(u16)]
 pub enum Event {
         Left = 0,
@@ -258,10 +281,111 @@ impl Event {
     }
 }
 
+#[cfg(not(all(not(target_os = "none"), feature = "host-input")))]
 unsafe extern "C" {
     fn eadk_event_get(timeout: &i32) -> Event;
 }
 
+/// Without Epsilon (feature `host-input`): no event can come without a source
+#[cfg(all(not(target_os = "none"), feature = "host-input"))]
+unsafe fn eadk_event_get(_timeout: &i32) -> Event {
+    panic!("No input source installed, see input::set_source()")
+}
+
+/// Waits for the next event, at most `timeout` milliseconds.
+///
+/// On the host, [`Event`] can't express an elapsed timeout: with an input source installed,
+/// `timeout` is ignored and the call waits for the next event of the source (see
+/// [`InputSource::next_event`]), so polling loops keep working. Use [`try_event_get`] to
+/// honor the timeout.
+///
+/// # Panics
+///
+/// On the host, panics if the installed input source has no event left.
 pub fn event_get(timeout: i32) -> Event {
+    #[cfg(not(target_os = "none"))]
+    if let Some(event) = with_source(|source| source.next_event()) {
+        return event.unwrap_or_else(|| panic!("Input source: no event left, see input::source_finished()"));
+    }
+
     unsafe { eadk_event_get(&timeout) }
+}
+
+/// Waits for the next event of the installed input source, at most `timeout` milliseconds.
+///
+/// Returns `None` if no event comes before the timeout. Without source, same as [`event_get`].
+#[cfg(not(target_os = "none"))]
+pub fn try_event_get(timeout: i32) -> Option<Event> {
+    with_source(|source| source.event_get(timeout)).unwrap_or_else(|| Some(unsafe { eadk_event_get(&timeout) }))
+}
+
+/// # Source of inputs replacing the keyboard on the host
+///
+/// Once installed with [`set_source`], [`KeyboardState::scan`] and [`event_get`] read the
+/// source instead of Epsilon (on the current thread), so tests can script the inputs.
+/// See [`replay::Replay`] for a timeline of inputs.
+///
+/// Sources work in the Epsilon simulator. To link plain host binaries and `cargo test` without it,
+/// enable the `host-input` feature (the keyboard is then released when no source is installed).
+#[cfg(not(target_os = "none"))]
+pub trait InputSource {
+    /// Keyboard state returned by [`KeyboardState::scan`]
+    fn scan(&mut self) -> KeyboardState;
+
+    /// Event returned by [`event_get`], waiting at most `timeout` milliseconds.
+    ///
+    /// Returns `None` if no event comes before the timeout (a negative timeout doesn't wait).
+    fn event_get(&mut self, timeout: i32) -> Option<Event>;
+
+    /// Event returned by [`event_get`]: waits for the next event, however long it takes.
+    ///
+    /// Returns `None` once the source has no event left. By default, polls
+    /// [`InputSource::event_get`] until an event comes or the source is [finished](InputSource::finished).
+    fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.event_get(i32::MAX) {
+                return Some(event);
+            }
+            if self.finished() {
+                return None;
+            }
+        }
+    }
+
+    /// Moves the clock of the source forward by `ms` milliseconds.
+    fn advance(&mut self, _ms: u64) {}
+
+    /// Returns `true` once the source has nothing more to play.
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+thread_local! {
+    static SOURCE: RefCell<Option<Box<dyn InputSource>>> = const { RefCell::new(None) };
+}
+
+/// Reads the inputs from `source` instead of the keyboard on the current thread.
+#[cfg(not(target_os = "none"))]
+pub fn set_source(source: impl InputSource + 'static) {
+    SOURCE.with(|current| *current.borrow_mut() = Some(Box::new(source)));
+}
+
+/// Removes the installed input source and returns it: inputs come from Epsilon again.
+#[cfg(not(target_os = "none"))]
+pub fn clear_source() -> Option<Box<dyn InputSource>> {
+    SOURCE.with(|current| current.borrow_mut().take())
+}
+
+/// Returns `true` if the installed input source has nothing more to play (`false` without source).
+#[cfg(not(target_os = "none"))]
+pub fn source_finished() -> bool {
+    with_source(|source| source.finished()).unwrap_or(false)
+}
+
+/// Calls `f` with the installed input source, `None` if there is none.
+#[cfg(not(target_os = "none"))]
+fn with_source<R>(f: impl FnOnce(&mut (dyn InputSource + 'static)) -> R) -> Option<R> {
+    SOURCE.with(|current| current.borrow_mut().as_deref_mut().map(f))
 }
\ No newline at end of file
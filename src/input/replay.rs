@@ -0,0 +1,285 @@
+//! # Scripted input replay
+//!
+//! A [`Replay`] is an [`InputSource`] playing a timeline of key presses, key releases, whole
+//! keyboard states and events, so a game loop can be driven end-to-end on the host with
+//! deterministic results.
+//!
+//! Times are in milliseconds on the replay clock. The clock starts at 0 and moves forward by
+//! [`Replay::with_scan_interval`] (one frame at 60 Hz by default) after each
+//! [`KeyboardState::scan`], or by [`advance`]. [`try_event_get`](crate::input::try_event_get)
+//! waits for the next scripted event at most `timeout` ms: the clock moves to the time of the
+//! event if it is due by then, else by `timeout` and `None` is returned.
+//! [`event_get`](crate::input::event_get) moves the clock to the next event whatever the
+//! timeout, so polling loops like `loop { match event_get(100) { ... } }` run unchanged, and
+//! only panics once the replay has no event left.
+//!
+//! When the [virtual clock](crate::timing::use_virtual_clock) is used, it is the replay clock:
+//! scripted times are compared with [`timing::millis`](crate::timing::millis), and scans, `msleep`
+//! and [`advance`] all move it. Set the scan interval to 0 if the game loop already sleeps. While
+//! the virtual clock is [frozen](crate::timing::freeze), only [`advance`] moves it.
+//!
+//! ```rust,ignore
+//! use eadkp::input::{self, replay::Replay, Key, KeyboardState};
+//!
+//! // From Rust...
+//! let replay = Replay::new().press(100, Key::Ok).release(200, Key::Ok);
+//! // ...or from a script
+//! let replay = Replay::from_script("t=100 press Ok; t=200 release Ok; t=250 event Back").unwrap();
+//!
+//! input::set_source(replay);
+//! while !input::source_finished() {
+//!     let keyboard = KeyboardState::scan();
+//!     game.update(keyboard);
+//! }
+//! input::clear_source();
+//! ```
+//!
+//! ## Script format
+//!
+//! Statements are separated by `;` or new lines, `#` starts a comment. Each statement is
+//! `t=<ms> <command> <name>`, with names written like [`Key`] and [`Event`] variants
+//! (case-insensitive):
+//!
+//! - `press <Key>` / `release <Key>`: change one key of the keyboard state
+//! - `state <mask>`: replace the whole keyboard state (decimal, or hexadecimal with `0x`)
+//! - `event <Event>`: queue an event for `event_get`
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use enum_iterator::all;
+
+use super::{Event, InputSource, Key, KeyboardState};
+use crate::timing;
+
+/// Default time between two scans: one frame at 60 Hz
+pub const DEFAULT_SCAN_INTERVAL: u64 = 16;
+
+/// Moves the clock of the installed input source forward by `ms` milliseconds (see [`InputSource::advance`]).
+pub fn advance(ms: u64) {
+    super::with_source(|source| source.advance(ms));
+}
+
+/// Change of the keyboard state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Change {
+    Press(Key),
+    Release(Key),
+    State(KeyboardState),
+}
+
+/// # Timeline of scripted inputs
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    /// Keyboard changes, sorted by time
+    changes: VecDeque<(u64, Change)>,
+    /// Events, sorted by time
+    events: VecDeque<(u64, Event)>,
+    keyboard: KeyboardState,
+    now: u64,
+    scan_interval: u64,
+}
+
+impl Replay {
+    /// Creates an empty timeline.
+    pub fn new() -> Self {
+        Replay { scan_interval: DEFAULT_SCAN_INTERVAL, ..Default::default() }
+    }
+
+    /// Presses `key` at `time`.
+    pub fn press(self, time: u64, key: Key) -> Self {
+        self.with_change(time, Change::Press(key))
+    }
+
+    /// Releases `key` at `time`.
+    pub fn release(self, time: u64, key: Key) -> Self {
+        self.with_change(time, Change::Release(key))
+    }
+
+    /// Replaces the whole keyboard state at `time`.
+    pub fn state(self, time: u64, state: KeyboardState) -> Self {
+        self.with_change(time, Change::State(state))
+    }
+
+    /// Queues `event` for [`event_get`](crate::input::event_get) at `time`.
+    pub fn event(mut self, time: u64, event: Event) -> Self {
+        let index = self.events.partition_point(|&(at, _)| at <= time);
+        self.events.insert(index, (time, event));
+        self
+    }
+
+    /// Sets the time between two scans (the clock doesn't move on its own with 0).
+    pub fn with_scan_interval(self, ms: u64) -> Self {
+        Replay { scan_interval: ms, ..self }
+    }
+
+    /// Parses a timeline script (see the [module documentation](self)).
+    pub fn from_script(script: &str) -> Result<Self, ScriptError> {
+        let mut replay = Replay::new();
+
+        let statements = script
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split(';'))
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty());
+
+        for statement in statements {
+            let error = |reason| ScriptError { statement: statement.to_string(), reason };
+            let mut words = statement.split_whitespace();
+
+            let time = words
+                .next()
+                .and_then(|word| word.strip_prefix("t="))
+                .ok_or(error("expected `t=<ms>`"))?
+                .parse()
+                .map_err(|_| error("invalid time"))?;
+            let command = words.next().ok_or(error("missing command"))?;
+            let argument = words.next().ok_or(error("missing argument"))?;
+            if words.next().is_some() {
+                return Err(error("too many words"));
+            }
+
+            replay = match command.to_ascii_lowercase().as_str() {
+                "press" => replay.press(time, parse_key(argument).ok_or(error("unknown key"))?),
+                "release" => replay.release(time, parse_key(argument).ok_or(error("unknown key"))?),
+                "state" => replay.state(time, parse_state(argument).ok_or(error("invalid keyboard state"))?),
+                "event" => replay.event(time, parse_event(argument).ok_or(error("unknown event"))?),
+                _ => return Err(error("unknown command")),
+            };
+        }
+
+        Ok(replay)
+    }
+
+    /// Current time of the replay clock in milliseconds
+    pub fn now(&self) -> u64 {
+        if timing::virtual_clock_enabled() {
+            timing::millis()
+        } else {
+            self.now
+        }
+    }
+
+    /// Moves the replay clock (the virtual clock when it is used) forward.
+    fn advance_clock(&mut self, ms: u64) {
+        if timing::virtual_clock_enabled() {
+            timing::advance(ms);
+        } else {
+            self.now += ms;
+        }
+    }
+
+    /// Waits on the replay clock: like [`advance_clock`](Self::advance_clock), but a
+    /// [frozen](timing::freeze) virtual clock doesn't move.
+    fn wait(&mut self, ms: u64) {
+        if timing::virtual_clock_enabled() {
+            timing::sleep_virtual(ms);
+        } else {
+            self.now += ms;
+        }
+    }
+
+    fn with_change(mut self, time: u64, change: Change) -> Self {
+        let index = self.changes.partition_point(|&(at, _)| at <= time);
+        self.changes.insert(index, (time, change));
+        self
+    }
+
+    /// Applies the keyboard changes due.
+    fn update(&mut self) {
+        let now = self.now();
+        while let Some(&(time, change)) = self.changes.front() {
+            if time > now {
+                break;
+            }
+            self.keyboard = match change {
+                Change::Press(key) => KeyboardState::from_raw(self.keyboard.raw() | 1 << key as u8),
+                Change::Release(key) => KeyboardState::from_raw(self.keyboard.raw() & !(1 << key as u8)),
+                Change::State(state) => state,
+            };
+            self.changes.pop_front();
+        }
+    }
+}
+
+impl InputSource for Replay {
+    fn scan(&mut self) -> KeyboardState {
+        self.update();
+        let keyboard = self.keyboard;
+        self.wait(self.scan_interval);
+        keyboard
+    }
+
+    /// Returns the next scripted event if it is due within `timeout` ms, moving the clock to its time.
+    ///
+    /// Otherwise the clock moves by `timeout` and `None` is returned, also when there are no more events.
+    fn event_get(&mut self, timeout: i32) -> Option<Event> {
+        let now = self.now();
+        let timeout = timeout.max(0) as u64;
+        let wait = self.events.front().map_or(timeout, |&(time, _)| time.saturating_sub(now).min(timeout));
+        self.wait(wait);
+        self.update();
+
+        let now = self.now();
+        if self.events.front().is_some_and(|&(time, _)| time <= now) {
+            return self.events.pop_front().map(|(_, event)| event);
+        }
+        None
+    }
+
+    fn next_event(&mut self) -> Option<Event> {
+        // Without event left, play the remaining keyboard changes so the replay ends finished
+        let target = match self.events.front() {
+            Some(&(time, _)) => time,
+            None => self.changes.back().map_or(0, |&(time, _)| time),
+        };
+
+        let now = self.now();
+        self.wait(target.saturating_sub(now));
+        let now = self.now();
+        assert!(now >= target, "Replay: the virtual clock is frozen at {} ms, the input at {} ms never comes", now, target);
+        self.update();
+
+        self.events.pop_front().map(|(_, event)| event)
+    }
+
+    fn advance(&mut self, ms: u64) {
+        self.advance_clock(ms);
+    }
+
+    fn finished(&self) -> bool {
+        self.changes.is_empty() && self.events.is_empty()
+    }
+}
+
+/// Invalid statement in a [`Replay`] script
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptError {
+    pub statement: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in `{}`", self.reason, self.statement)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn parse_key(name: &str) -> Option<Key> {
+    all::<Key>().find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+fn parse_event(name: &str) -> Option<Event> {
+    all::<Event>().find(|event| format!("{:?}", event).eq_ignore_ascii_case(name))
+}
+
+fn parse_state(mask: &str) -> Option<KeyboardState> {
+    let raw = match mask.strip_prefix("0x").or_else(|| mask.strip_prefix("0X")) {
+        Some(hexadecimal) => u64::from_str_radix(hexadecimal, 16).ok()?,
+        None => mask.parse().ok()?,
+    };
+    Some(KeyboardState::from_raw(raw))
+}
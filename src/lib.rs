@@ -25,11 +25,17 @@ pub mod random;
 pub mod battery;
 pub mod input;
 pub mod storage;
+pub mod system;
+pub mod text;
 
 // Module builder uniquement disponible pour les build scripts (OS hôte, pas embarqué)
 #[cfg(all(not(target_os = "none"), feature = "build-tools"))]
 pub mod builder;
 
+// Module de tests par capture d'écran uniquement disponible sur l'OS hôte
+#[cfg(all(not(target_os = "none"), feature = "testing"))]
+pub mod testing;
+
 // Réexportations du contenu des modules
 pub use utils::*;
 pub use color::*;
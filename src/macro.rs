@@ -33,33 +33,22 @@ macro_rules! eadk_setup {
         #[cfg(target_os = "none")]
         #[panic_handler]
         fn panic(panic: &PanicInfo<'_>) -> ! {
-            fn write_wrapped(text: &str, limit: usize) {
-                use alloc::string::String;
-                let mut line_count = 0;
-                let mut line = String::new();
-                
-                for i in 0..text.len() {
-                    line.push(text.as_bytes()[i] as char);
-                    if line.len() >= limit || text.as_bytes()[i] as char == '\n' || i >= text.len() - 1 {
-                        eadkp::display::draw_string(
-                            line.as_str(),
-                            eadkp::Point { x: 10, y: (10 + 20 * line_count) as u16 },
-                            false,
-                            eadkp::Color { rgb565: 65503 },
-                            eadkp::Color { rgb565: 63488 },
-                        );
-                        line.clear();
-                        line_count += 1;
-                    }
-                }
-            }
-
             eadkp::display::push_rect_uniform(
                 eadkp::Rect { x: 0, y: 0, width: 320, height: 240 },
                 eadkp::Color { rgb565: 63488 },
             );
             
-            write_wrapped(format!("{}", panic).as_str(), 42);
+            // Message coupé entre les mots (UTF-8 compris) sur toute la largeur de l'écran
+            eadkp::display::draw_text_box(
+                eadkp::Rect { x: 10, y: 10, width: 300, height: 220 },
+                format!("{}", panic).as_str(),
+                eadkp::display::Align::TOP_LEFT,
+                eadkp::SMALL_FONT,
+                eadkp::display::TextColors {
+                    text: eadkp::Color { rgb565: 65503 },
+                    background: eadkp::Color { rgb565: 63488 },
+                },
+            );
             
             loop {
                 eadkp::timing::msleep(50);
@@ -143,4 +132,29 @@ macro_rules! include_image {
             ".eif",
         ))
     };
-}
\ No newline at end of file
+}
+
+/// Macro to include a font converted at build time from the `assets/fonts` directory.
+///
+/// The font is made of `assets/fonts/<name>.png` (glyph atlas) and `assets/fonts/<name>.txt` (character list).
+///
+/// ## Example
+/// Usage:
+/// ```rust,ignore
+/// static PIXEL_FONT: Option<eadkp::text::Font> = eadkp::text::Font::from_raw(include_font!("pixel"));
+/// ```
+/// Output:
+/// ```rust,ignore
+/// static PIXEL_FONT: Option<eadkp::text::Font> = eadkp::text::Font::from_raw(include_bytes!("<OUT_DIR>/assets/fonts/pixel.eff"));
+/// ```
+#[macro_export]
+macro_rules! include_font {
+    ($name:literal) => {
+        include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/assets/fonts/",
+            $name,
+            ".eff",
+        ))
+    };
+}
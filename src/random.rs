@@ -1,29 +1,34 @@
+use core::sync::atomic::{AtomicU32, Ordering};
 
+use rand_core::{impls, RngCore, SeedableRng};
 
 unsafe extern "C" {
     fn eadk_random() -> u32;
 }
 
 /// Cache de state pour générateur ultra-rapide
-static mut FAST_RNG_STATE: u32 = 0x12345678;
+/// (atomique : `random_c` peut être appelé depuis une interruption sans comportement indéfini)
+static FAST_RNG_STATE: AtomicU32 = AtomicU32::new(0x12345678);
 
 /// Returns a random `u32` value - version ultra-optimisée
 /// Utilise un Xorshift32 rapide avec reseeding périodique
 #[inline(always)]
 pub fn random_c() -> u32 {
-    unsafe {
-        // Reseed depuis le hardware tous les 256 appels
-        if FAST_RNG_STATE & 0xFF == 0 {
-            FAST_RNG_STATE = eadk_random();
-            if FAST_RNG_STATE == 0 { FAST_RNG_STATE = 1; }
-        }
-        
-        // Xorshift32 ultra-rapide (3 cycles ARM)
-        FAST_RNG_STATE ^= FAST_RNG_STATE << 13;
-        FAST_RNG_STATE ^= FAST_RNG_STATE >> 17;
-        FAST_RNG_STATE ^= FAST_RNG_STATE << 5;
-        FAST_RNG_STATE
+    let mut state = FAST_RNG_STATE.load(Ordering::Relaxed);
+
+    // Reseed depuis le hardware tous les 256 appels
+    if state & 0xFF == 0 {
+        state = unsafe { eadk_random() };
+        if state == 0 { state = 1; }
     }
+
+    // Xorshift32 ultra-rapide (3 cycles ARM)
+    state ^= state << 13;
+    state ^= state >> 17;
+    state ^= state << 5;
+
+    FAST_RNG_STATE.store(state, Ordering::Relaxed);
+    state
 }
 
 /// Force hardware random - bypasse le cache rapide
@@ -36,24 +41,33 @@ pub fn random_hardware() -> u32 {
 /// Ultra-optimized for ARM Cortex-M7: avoids expensive modulo, uses bit manipulation
 #[inline(always)]
 pub fn randint(min: u64, max: u64) -> u64 {
+    randint_with(|| unsafe { eadk_random() }, min, max)
+}
+
+/// `randint` sur n'importe quelle source de `u32` aléatoires (hardware ou [`Rng`])
+#[inline(always)]
+fn randint_with(mut next: impl FnMut() -> u32, min: u64, max: u64) -> u64 {
     debug_assert!(min <= max, "randint: min cannot be greater than max");
     
     if min == max {
         return min;
     }
     
-    let range = max - min + 1;
+    let range = max.wrapping_sub(min).wrapping_add(1);
+
+    // Plage complète (0..=u64::MAX) : tous les u64 conviennent
+    if range == 0 {
+        return (next() as u64) | ((next() as u64) << 32);
+    }
     
     // Ultra-fast path pour puissances de 2 (bit masking)
     if range & (range.wrapping_sub(1)) == 0 {
         let mask = range - 1;
         if range <= 0x1_0000_0000 { // <= 2^32
-            return min + ((unsafe { eadk_random() } as u64) & mask);
+            return min + ((next() as u64) & mask);
         } else {
             // Génération 64-bit optimisée sans division
-            let rand_u64 = unsafe {
-                (eadk_random() as u64) | ((eadk_random() as u64) << 32)
-            };
+            let rand_u64 = (next() as u64) | ((next() as u64) << 32);
             return min + (rand_u64 & mask);
         }
     }
@@ -62,7 +76,7 @@ pub fn randint(min: u64, max: u64) -> u64 {
     if range <= 0x1_0000_0000 {
         let threshold = (0x1_0000_0000_u64 / range) * range;
         loop {
-            let rand_val = unsafe { eadk_random() } as u64;
+            let rand_val = next() as u64;
             if rand_val < threshold {
                 return min + (rand_val % range);
             }
@@ -72,9 +86,7 @@ pub fn randint(min: u64, max: u64) -> u64 {
     // 64-bit path optimisé
     let threshold = (u64::MAX / range) * range;
     loop {
-        let rand_u64 = unsafe {
-            (eadk_random() as u64) | ((eadk_random() as u64) << 32)
-        };
+        let rand_u64 = (next() as u64) | ((next() as u64) << 32);
         if rand_u64 < threshold {
             return min + (rand_u64 % range);
         }
@@ -85,8 +97,13 @@ pub fn randint(min: u64, max: u64) -> u64 {
 /// Ultra-optimized: uses bit manipulation to avoid expensive float conversion
 #[inline(always)]
 pub fn random_f64() -> f64 {
+    unit_f64(unsafe { eadk_random() })
+}
+
+/// Convertit 32 bits aléatoires en f64 dans [0.0, 1.0)
+#[inline(always)]
+fn unit_f64(rand_bits: u32) -> f64 {
     // Manipulation directe des bits IEEE 754 pour éviter la conversion coûteuse
-    let rand_bits = unsafe { eadk_random() };
     // Utilise les 32 bits dans la mantisse, exponent = 1023 (bias pour [1.0, 2.0))
     let float_bits = 0x3FF0_0000_0000_0000_u64 | ((rand_bits as u64) << 20);
     f64::from_bits(float_bits) - 1.0
@@ -99,9 +116,7 @@ pub fn random_f64_range(min: f64, max: f64) -> f64 {
     debug_assert!(min < max, "random_f64_range: min must be less than max");
     
     // Génération inline pour éviter l'overhead d'appel
-    let rand_bits = unsafe { eadk_random() };
-    let float_bits = 0x3FF0_0000_0000_0000_u64 | ((rand_bits as u64) << 20);
-    let rnd = f64::from_bits(float_bits) - 1.0;
+    let rnd = unit_f64(unsafe { eadk_random() });
     
     // FMA optimisé manuellement (évite la dépendance libm)
     min + (max - min) * rnd
@@ -119,9 +134,148 @@ pub fn random_bool() -> bool {
 pub fn random_bool_with_probability(probability: f64) -> bool {
     debug_assert!((0.0..=1.0).contains(&probability), "probability must be between 0.0 and 1.0");
     
+    unsafe { eadk_random() <= probability_threshold(probability) }
+}
+
+/// Seuil 32-bit d'une probabilité
+#[inline(always)]
+fn probability_threshold(probability: f64) -> u32 {
     // Conversion optimisée vers seuil 32-bit avec saturation
     let threshold = (probability * 4294967296.0) as u64; // 2^32 pour meilleure précision
-    let threshold = threshold.min(0xFFFFFFFF) as u32; // Saturation au lieu de branches
-    
-    unsafe { eadk_random() <= threshold }
+    threshold.min(0xFFFFFFFF) as u32 // Saturation au lieu de branches
+}
+
+// ==================================================================================
+// =================================== SEEDED RNG ===================================
+// ==================================================================================
+
+/// # Seedable pseudo-random generator (xoshiro128++)
+///
+/// Unlike the free functions of this module, which read the hardware generator, an `Rng`
+/// gives the same sequence for the same seed: replays, procedural levels and tests are
+/// reproducible. It implements [`RngCore`] and [`SeedableRng`], so the whole `rand`
+/// ecosystem (distributions, shuffling...) can use it.
+///
+/// ```rust,ignore
+/// use eadkp::random::Rng;
+///
+/// let mut level_rng = Rng::seed(level_number);    // Same level every time
+/// let mut enemy_rng = Rng::from_hardware();       // Different every run
+///
+/// let width = level_rng.randint(10, 20);
+/// let dodge = enemy_rng.random_bool_with_probability(0.25);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: [u32; 4],
+}
+
+impl Rng {
+    /// Creates a generator from a 64-bit seed (expanded with SplitMix64).
+    pub fn seed(seed: u64) -> Self {
+        let mut splitmix = seed;
+        let mut next = || {
+            splitmix = splitmix.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = splitmix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        let (low, high) = (next(), next());
+        Rng::from_state([low as u32, (low >> 32) as u32, high as u32, (high >> 32) as u32])
+    }
+
+    /// Creates a generator seeded by the hardware random generator.
+    pub fn from_hardware() -> Self {
+        Rng::from_state(core::array::from_fn(|_| random_hardware()))
+    }
+
+    /// L'état nul est le seul qui ne produit que des zéros
+    fn from_state(state: [u32; 4]) -> Self {
+        if state == [0; 4] {
+            return Rng::seed(0);
+        }
+        Rng { state }
+    }
+
+    /// Returns the next random `u32`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s0.wrapping_add(*s3).rotate_left(7).wrapping_add(*s0);
+
+        let t = *s1 << 9;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(11);
+
+        result
+    }
+
+    /// Returns the next random `u64`.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        (self.next_u32() as u64) | ((self.next_u32() as u64) << 32)
+    }
+
+    /// Generates a random u64 number between min and max (inclusive), see [`randint`].
+    pub fn randint(&mut self, min: u64, max: u64) -> u64 {
+        randint_with(|| self.next_u32(), min, max)
+    }
+
+    /// Generates a random f64 between 0.0 and 1.0 (exclusive of 1.0)
+    pub fn random_f64(&mut self) -> f64 {
+        unit_f64(self.next_u32())
+    }
+
+    /// Generates a random f64 between min and max
+    pub fn random_f64_range(&mut self, min: f64, max: f64) -> f64 {
+        debug_assert!(min < max, "random_f64_range: min must be less than max");
+        min + (max - min) * self.random_f64()
+    }
+
+    /// Generates a random boolean with 50% probability
+    pub fn random_bool(&mut self) -> bool {
+        self.next_u32() >> 31 != 0
+    }
+
+    /// Generates a random boolean with given probability
+    pub fn random_bool_with_probability(&mut self, probability: f64) -> bool {
+        debug_assert!((0.0..=1.0).contains(&probability), "probability must be between 0.0 and 1.0");
+        self.next_u32() <= probability_threshold(probability)
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        Rng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dst)
+    }
+}
+
+impl SeedableRng for Rng {
+    type Seed = [u8; 16];
+
+    /// Uses the 16 bytes as the xoshiro128++ state (4 little-endian words).
+    fn from_seed(seed: Self::Seed) -> Self {
+        Rng::from_state(core::array::from_fn(|index| {
+            u32::from_le_bytes([seed[index * 4], seed[index * 4 + 1], seed[index * 4 + 2], seed[index * 4 + 3]])
+        }))
+    }
+
+    /// Same as [`Rng::seed`].
+    fn seed_from_u64(state: u64) -> Self {
+        Rng::seed(state)
+    }
 }
\ No newline at end of file
@@ -1,5 +1,3 @@
-
-
 /*!
 # Storage Management Module for Epsilon Applications on Numworks Calculators.
 
@@ -21,6 +19,24 @@ behaviorally.
     - **N0120** (untested, but theoretically supported)
     - **N0110** (untested, but theoretically supported)
 
+  The detected model and storage layout are available through
+  [`crate::system::userland`].
+
+- On host targets (simulator, `cargo test`), the storage is emulated in
+  memory with the same record layout as Epsilon (`0xBADD0BEE` magic,
+  `[u16 size][name\0][content]` records) and a fixed capacity of
+  [`HOST_STORAGE_SIZE`] bytes. Each thread gets its own storage, which can
+  be cleared with [`host_reset`].
+
+- The `file_*` functions are `unsafe` and hand out raw pointers into the
+  storage. Prefer the safe API: [`read`], [`read_into`] and [`write`] work
+  on owned buffers, and the [`Storage`] handle gives borrowed views whose
+  lifetime prevents any write or erase while they are in use.
+
+- Every record is bounds-checked while walking the chain. A corrupted record
+  stops the walk and writes fail with [`StorageError::CorruptedStorage`]
+  until [`repair`] truncates the chain (see [`verify`]).
+
 ## Credits
 
 Original author: **[Yaya Cout](https://framagit.org/Yaya.Cout)**
@@ -37,8 +53,16 @@ Special thanks to Yaya Cout for his remarkable engineering work on storage
 manipulation, without which this module would probably never have come to life.
 */
 
+use core::marker::PhantomData;
 use core::ptr;
 use heapless;
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(target_os = "none")]
+use alloc::vec::Vec;
+
+#[cfg(target_os = "none")]
+use alloc::string::{String, ToString};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StorageError {
@@ -54,8 +78,24 @@ pub enum StorageError {
     InvalidMagicNumber { expected: u32, found: u32 },
     /// Stockage plein, position libre null
     StorageFull,
+    /// Un fichier portant ce nom existe déjà
+    FileAlreadyExists,
     /// Dépassement de la taille du stockage
     StorageOverflow { available: usize, needed: usize },
+    /// Un handle [`Storage`] est déjà actif, le stockage ne peut pas être modifié ailleurs
+    Locked,
+    /// Le buffer fourni est trop petit pour recevoir le contenu du fichier
+    BufferTooSmall { needed: usize },
+    /// Échec de la sérialisation postcard d'une valeur
+    SerializationError,
+    /// Contenu illisible : en-tête de sauvegarde absent ou échec de la désérialisation postcard
+    DecodeError,
+    /// Version de schéma de la sauvegarde différente de celle attendue
+    VersionMismatch { expected: u16, found: u16 },
+    /// Checksum de la sauvegarde incorrect (données corrompues)
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// Chaîne d'enregistrements corrompue à `offset` (depuis le début du stockage), voir [`verify`] et [`repair`]
+    CorruptedStorage { offset: usize },
 }
 
 pub type Result<T> = core::result::Result<T, StorageError>;
@@ -107,7 +147,6 @@ unsafe fn strcmp(s1: *const u8, s2: *const u8) -> bool {
 /// Copie n bytes de src vers dest (zones non chevauchantes)
 /// 
 /// **Comportement INDÉFINI en cas de CHEVAUCHEMENT des zones !** NON SÉCURISÉ .
-#[cfg(target_os = "none")]
 unsafe fn memcpy(dest: *mut u8, src: *const u8, n: usize) {
     unsafe { ptr::copy_nonoverlapping(src, dest, n) }
 }
@@ -115,13 +154,11 @@ unsafe fn memcpy(dest: *mut u8, src: *const u8, n: usize) {
 /// Copie `n` bytes de `src:*` vers `dest:*` (zones peuvent chevaucher)
 /// 
 /// **Comportement défini même en cas de chevauchement:** La copie se fait par une mémoire tampon.
-#[cfg(target_os = "none")]
 unsafe fn memmove(dest: *mut u8, src: *const u8, n: usize) {
     unsafe { ptr::copy(src, dest, n) }
 }
 
 /// Remplit n bytes avec la valeur c
-#[cfg(target_os = "none")]
 unsafe fn memset(s: *mut u8, c: u8, n: usize) {
     for i in 0..n {
         unsafe { *s.add(i) = c };
@@ -137,116 +174,41 @@ unsafe fn memset(s: *mut u8, c: u8, n: usize) {
 /// ## attention:
 /// Le contenu écrit doit être en bytes bruts. Pour écrire du texte, utilisez `write_file_string` qui gère l'encodage UTF-8 et le null terminator. 
 /// 
+/// Retourne [`StorageError::FileAlreadyExists`] si le fichier existe déjà, voir [`file_replace`] pour l'écraser.
+/// 
 /// Format: \[2 bytes taille\] \[nom\0\] \[contenu\]
-#[cfg(target_os = "none")]
 pub unsafe fn file_write_raw(filename: &str, content: &[u8]) -> Result<()> {
 
     let filename_cstr = to_cstring(filename)?;
-    let filename_ptr = filename_cstr.as_ptr();
-    let filename_len = filename_cstr.len(); // Avec le null terminator !
-
-    let content_ptr = content.as_ptr();
-    let content_len = content.len();
 
     unsafe {
-        // Trouver la position libre dans le stockage
-        let free_pos = next_free();
-        if free_pos.is_null() { 
-            return Err(StorageError::StorageFull); 
-        }
-        
-        // Calculer la taille totale nécessaire
-        let total_size = 2 + filename_len + content_len; // taille_header + nom (avec null terminator) + contenu
-        let storage_end = (address() + size()) as usize;
-        let free_pos_usize = free_pos as usize;
-        let needed_end = free_pos_usize + total_size;
-        
-        // Vérifier qu'on a assez d'espace avec info détaillée
-        if needed_end > storage_end { 
-            return Err(StorageError::StorageOverflow { 
-                available: storage_end.saturating_sub(free_pos_usize),
-                needed: total_size,
-            }); 
+        // Ne jamais créer deux enregistrements avec le même nom
+        if find_record(filename_cstr.as_ptr())?.is_some() {
+            return Err(StorageError::FileAlreadyExists);
         }
-        
-        // Écrire le header (taille totale sur 2 bytes)
-        let write_pos = free_pos as *mut u8;
-        ptr::write_unaligned(write_pos as *mut u16, total_size as u16);
-        
-        // Écrire le nom du fichier (avec null terminator)
-        let name_pos = write_pos.add(2);
-        memcpy(name_pos, filename_ptr, filename_len);
-        
-        // Écrire le contenu
-        let content_pos = name_pos.add(filename_len);
-        memcpy(content_pos, content_ptr, content_len);
-        
-        // Nettoyer le reste (marquer la fin des enregistrements)
-        let cleanup_pos = content_pos.add(content_len);
-        let cleanup_size = ((address() + size()) as *mut u8).offset_from(cleanup_pos) as usize;
-        memset(cleanup_pos, 0, cleanup_size);
-        
-        Ok(())
+
+        append_record(&filename_cstr, content)
     }
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_write_raw(_filename: &str, _content: &[u8]) -> Result<()> {
-    Ok(())
-}
 
 
 /// Lit un fichier et retourne un pointeur vers son contenu
-#[cfg(target_os = "none")]
 pub unsafe fn file_read_raw(filename: &str) -> Result<(*const u8, usize)> {
 
     let filename_cstr = to_cstring(filename)?;
-    let filename_ptr = filename_cstr.as_ptr();
 
     unsafe {
-        let storage_addr = address();
-        let mut offset = (storage_addr as *mut u8).add(4); // Skip magic number
-        let end_addr = (storage_addr + size()) as *mut u8;
-        
-        // Vérifier que le stockage est valide avec info sur le magic number
-        let magic_expected = 0xBADD0BEEu32.swap_bytes();
-        let magic_found = ptr::read_unaligned(storage_addr as *const u32);
-        if magic_found != magic_expected {
-            return Err(StorageError::InvalidMagicNumber { 
-                expected: magic_expected, 
-                found: magic_found 
-            });
-        }
-        
-        // Parcourir tous les enregistrements
-        while offset < end_addr {
-            let size = ptr::read_unaligned(offset as *const u16);
-            if size == 0 { break; } // Fin des enregistrements
-            
-            let name = offset.add(2);
-            if strcmp(name, filename_ptr) { // Fichier trouvé
-                let name_size = strlen(name) + 1;
-                let content_size = size as usize - 2 - name_size;
-                return Ok((offset.add(2 + name_size), content_size));
-            }
-            
-            offset = offset.add(size as usize);
+        match find_record(filename_cstr.as_ptr())? {
+            Some(record) => Ok(record.content()),
+            None => Err(StorageError::FileNotFound),
         }
-        
-        Err(StorageError::FileNotFound)
     }
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_read_raw(_filename: &str) -> Result<(*const u8, usize)> {
-    Err(StorageError::InvalidStorage)
-}
 
 
 /// Vérifie si un fichier existe dans le stockage
-#[cfg(target_os = "none")]
 pub fn file_exists(filename: &str) -> bool {
     match unsafe { file_read_raw(filename) } {
         Ok(_) => true,
@@ -255,67 +217,96 @@ pub fn file_exists(filename: &str) -> bool {
     }
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_exists(_filename: &str) -> bool {
-    false
-}
 
 
 /// Supprime un fichier du stockage
-#[cfg(target_os = "none")]
 pub unsafe fn file_erase(filename: &str) -> Result<()> {
 
     let filename_cstr = to_cstring(filename)?;
-    let filename_ptr = filename_cstr.as_ptr();
 
     unsafe {
-        let storage_addr = address();
-        let mut offset = (storage_addr as *mut u8).add(4);
-        let end_addr = (storage_addr + size()) as *mut u8;
-        
-        // Vérifier que le stockage est valide
-        let magic_expected = 0xBADD0BEEu32.swap_bytes();
-        let magic_found = ptr::read_unaligned(storage_addr as *const u32);
-        if magic_found != magic_expected {
-            return Err(StorageError::InvalidMagicNumber { 
-                expected: magic_expected, 
-                found: magic_found 
-            });
+        match find_record(filename_cstr.as_ptr())? {
+            Some(record) => erase_record(record),
+            None => Err(StorageError::FileNotFound),
         }
-        
-        // Chercher le fichier
-        while offset < end_addr {
-            let size = ptr::read_unaligned(offset as *const u16);
-            if size == 0 { break; }
-            
-            let name = offset.add(2);
-            if strcmp(name, filename_ptr) { // Fichier trouvé
-                // Déplacer tous les enregistrements suivants pour combler le trou
-                let next_free_pos = next_free() as *mut u8;
-                let move_size = next_free_pos.offset_from(offset) as usize;
-                memmove(offset, offset.add(size as usize), move_size);
-                
-                // Nettoyer l'espace libéré
-                memset(next_free_pos.sub(size as usize), 0, size as usize);
-                return Ok(());
-            }
-            
-            offset = offset.add(size as usize);
+    }
+}
+
+
+/// Écrit un fichier dans le stockage, en remplaçant son contenu s'il existe déjà
+/// 
+/// Le nouvel enregistrement est écrit entièrement à la fin de la chaîne avant
+/// que l'ancien ne soit retiré : en cas de coupure pendant l'écriture, l'ancien
+/// contenu reste lisible. Il faut donc assez d'espace libre pour les deux
+/// versions le temps de l'opération, sinon [`StorageError::StorageOverflow`]
+/// est retourné et l'ancien fichier est conservé.
+///
+/// Si la coupure survient après l'écriture mais avant le retrait, les deux
+/// versions restent dans la chaîne : les lectures voient l'ancienne jusqu'à
+/// [`repair`], qui garde la nouvelle (le dernier enregistrement complet).
+///
+/// # Safety
+///
+/// Les enregistrements qui suivent l'ancienne version sont déplacés : les
+/// pointeurs obtenus avec [`file_read_raw`] ne doivent plus être utilisés
+/// après l'appel. Il ne doit pas non plus y avoir de [`Storage`] actif (hors
+/// de ses propres méthodes), dont les contenus empruntés seraient invalidés.
+pub unsafe fn file_replace(filename: &str, content: &[u8]) -> Result<()> {
+
+    let filename_cstr = to_cstring(filename)?;
+
+    unsafe {
+        let old_record = find_record(filename_cstr.as_ptr())?;
+
+        // Écrire la nouvelle version (l'ancienne, placée avant, reste celle trouvée par les lectures)
+        append_record(&filename_cstr, content)?;
+
+        // Retirer l'ancienne version une fois la nouvelle complète
+        if let Some(record) = old_record {
+            erase_record(record)?;
         }
-        
-        Err(StorageError::FileNotFound)
+
+        Ok(())
     }
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_erase(_filename: &str) -> Result<()> {
-    Ok(())
+
+/// Renomme un fichier du stockage
+/// 
+/// Comme pour [`file_replace`], l'enregistrement renommé est écrit entièrement
+/// avant que l'ancien ne soit retiré. Retourne [`StorageError::FileAlreadyExists`]
+/// si `new_filename` est déjà utilisé.
+///
+/// # Safety
+///
+/// Mêmes conditions que [`file_replace`] : l'ancien enregistrement est retiré
+/// et les suivants déplacés, ce qui invalide les pointeurs de [`file_read_raw`].
+/// Le stockage ne doit pas être réservé par un [`Storage`] actif ailleurs.
+pub unsafe fn file_rename(old_filename: &str, new_filename: &str) -> Result<()> {
+
+    let old_cstr = to_cstring(old_filename)?;
+    let new_cstr = to_cstring(new_filename)?;
+
+    unsafe {
+        let record = find_record(old_cstr.as_ptr())?.ok_or(StorageError::FileNotFound)?;
+
+        if old_filename == new_filename {
+            return Ok(());
+        }
+
+        if find_record(new_cstr.as_ptr())?.is_some() {
+            return Err(StorageError::FileAlreadyExists);
+        }
+
+        // Copier le contenu sous le nouveau nom, puis retirer l'ancien enregistrement
+        let (content_ptr, content_len) = record.content();
+        append_record(&new_cstr, core::slice::from_raw_parts(content_ptr, content_len))?;
+        erase_record(record)
+    }
 }
 
+
 /// Écrit une string dans le stockage (avec encodage UTF-8 et null terminator)
-#[cfg(target_os = "none")]
 pub unsafe fn file_write_string(filename: &str, content: &str) -> Result<()> {
     let content_cstr = to_cstring(content)?;
     let content_bytes = content_cstr.as_slice(); // Obtenir les octets, y compris le null terminator
@@ -323,13 +314,7 @@ pub unsafe fn file_write_string(filename: &str, content: &str) -> Result<()> {
     unsafe { file_write_raw(filename, content_bytes) }
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_write_string(_filename: &str, _content: &str) -> Result<()> {
-    Ok(())
-}
 
-#[cfg(target_os = "none")]
 pub unsafe fn file_read_string(filename: &str) -> Result<&'static str> {
     // Obtenir les bytes bruts du fichier
     let (content_ptr, content_len) = unsafe { file_read_raw(filename)? };
@@ -349,82 +334,1415 @@ pub unsafe fn file_read_string(filename: &str) -> Result<&'static str> {
     return cstring_to_str(cstr_ptr)
 }
 
-/// Dummy version
-#[cfg(not(target_os = "none"))]
-pub unsafe fn file_read_string(_filename: &str) -> Result<&'static str> {
-    Ok("Dummy content")
-}
 
 // ============================================================================
-// HARDWARE INTERFACE
+// RECORD CHAIN
 // ============================================================================
 
-/// Retourne l'adresse de base du stockage
-#[cfg(target_os = "none")]
-unsafe fn address() -> u32 {
-    unsafe { ptr::read_unaligned((userland_address() + 0xC) as *const u32) }
+/// Enregistrement brut dans le stockage : \[2 bytes taille\] \[nom\0\] \[contenu\]
+#[derive(Clone, Copy)]
+struct RawRecord {
+    /// Début de l'enregistrement (champ taille)
+    start: *mut u8,
+    /// Taille totale de l'enregistrement (header + nom + contenu)
+    size: usize,
 }
 
-/// Retourne la taille totale du stockage
-#[cfg(target_os = "none")]
-unsafe fn size() -> u32 {
-    unsafe { ptr::read_unaligned((userland_address() + 0x10) as *const u32) }
+impl RawRecord {
+    /// Pointeur vers le nom (C string)
+    unsafe fn name_ptr(&self) -> *const u8 {
+        unsafe { self.start.add(2) }
+    }
+
+    /// Nom de l'enregistrement, `None` s'il n'est pas en UTF-8
+    unsafe fn name<'a>(&self) -> Option<&'a str> {
+        cstring_to_str(unsafe { self.name_ptr() }).ok()
+    }
+
+    /// Pointeur et taille du contenu
+    unsafe fn content(&self) -> (*const u8, usize) {
+        unsafe {
+            let name_size = strlen(self.name_ptr()) + 1;
+            (self.start.add(2 + name_size), self.size - 2 - name_size)
+        }
+    }
 }
 
-/// Trouve la prochaine position libre dans le stockage
-#[cfg(target_os = "none")]
-unsafe fn next_free() -> *const u32 {
+/// Itérateur sur la chaîne d'enregistrements
+/// 
+/// Chaque enregistrement est vérifié avant d'être retourné (taille, limites du
+/// stockage, nom terminé par `\0`) : un enregistrement corrompu arrête le
+/// parcours et est mémorisé dans `problem`, au lieu de faire lire hors du
+/// stockage ou boucler indéfiniment.
+/// 
+/// Une fois épuisé, `offset` pointe sur la position libre (ou sur l'enregistrement corrompu).
+struct RawRecords {
+    base: *mut u8,
+    offset: *mut u8,
+    end: *mut u8,
+    problem: Option<RecordProblem>,
+}
+
+impl RawRecords {
+    /// Vérifie l'enregistrement à la position courante
+    unsafe fn check(&self, size: usize) -> Option<RecordProblemKind> {
+        let available = unsafe { self.end.offset_from(self.offset) } as usize;
+
+        // Header + au moins le null terminator du nom
+        if size < 3 {
+            return Some(RecordProblemKind::InvalidSize { size: size as u16 });
+        }
+
+        if size > available {
+            return Some(RecordProblemKind::OutOfBounds { size: size as u16, available });
+        }
+
+        // Le nom doit se terminer à l'intérieur de l'enregistrement
+        let name = unsafe { core::slice::from_raw_parts(self.offset.add(2), size - 2) };
+        if !name.contains(&0) {
+            return Some(RecordProblemKind::UnterminatedName);
+        }
+
+        None
+    }
+
+    /// Position libre à la fin de la chaîne (après épuisement de l'itérateur)
+    fn end_of_chain(&self) -> Result<*mut u8> {
+        match self.problem {
+            Some(problem) => Err(StorageError::CorruptedStorage { offset: problem.offset }),
+            None => Ok(self.offset),
+        }
+    }
+}
+
+impl Iterator for RawRecords {
+    type Item = RawRecord;
+
+    fn next(&mut self) -> Option<RawRecord> {
+        if self.problem.is_some() {
+            return None;
+        }
+
+        // Pas la place pour un header : fin du stockage
+        if unsafe { self.end.offset_from(self.offset) } < 2 {
+            return None;
+        }
+
+        let size = unsafe { ptr::read_unaligned(self.offset as *const u16) } as usize;
+        if size == 0 { return None; } // Fin des enregistrements
+
+        if let Some(kind) = unsafe { self.check(size) } {
+            let offset = unsafe { self.offset.offset_from(self.base) } as usize;
+            self.problem = Some(RecordProblem { offset, kind });
+            return None;
+        }
+
+        let record = RawRecord { start: self.offset, size };
+        self.offset = unsafe { self.offset.add(size) };
+        Some(record)
+    }
+}
+
+/// Cherche l'enregistrement portant le nom donné (C string)
+unsafe fn find_record(filename_ptr: *const u8) -> Result<Option<RawRecord>> {
     unsafe {
-        let storage_addr = address();
-        let mut offset = (storage_addr as *mut u8).add(4);
-        let end_addr = (storage_addr + size()) as *mut u8;
+        let mut records = raw_records()?;
+        if let Some(record) = records.by_ref().find(|record| strcmp(record.name_ptr(), filename_ptr)) {
+            return Ok(Some(record));
+        }
+
+        // Le fichier pourrait se trouver après un enregistrement corrompu
+        records.end_of_chain()?;
+        Ok(None)
+    }
+}
+
+/// Ajoute un enregistrement à la fin de la chaîne
+/// 
+/// Le champ taille est écrit en dernier : tant qu'il vaut 0, l'enregistrement
+/// n'est pas visible et la chaîne se termine toujours à l'ancienne position libre.
+unsafe fn append_record(filename_cstr: &[u8], content: &[u8]) -> Result<()> {
+    let filename_len = filename_cstr.len(); // Avec le null terminator !
+    let content_len = content.len();
+
+    unsafe {
+        // Trouver la position libre dans le stockage
+        let free_pos = next_free()?;
         
-        // Vérifier validité mais ignorer l'erreur (retourne null si invalide)
-        if is_valid(storage_addr as *const u32).is_err() { return ptr::null(); }
+        // Calculer la taille totale nécessaire
+        let total_size = 2 + filename_len + content_len; // taille_header + nom (avec null terminator) + contenu
+        let storage_end = address() + size();
+        let free_pos_usize = free_pos as usize;
+        let needed_end = free_pos_usize + total_size;
         
-        // Parcourir jusqu'à trouver un enregistrement vide (size=0)
-        while offset < end_addr {
-            let size = ptr::read_unaligned(offset as *const u16);
-            if size == 0 { return offset as *const u32; }
-            offset = offset.add(size as usize);
+        // Vérifier qu'on a assez d'espace avec info détaillée (la taille doit aussi tenir sur 2 bytes)
+        if needed_end > storage_end || total_size > u16::MAX as usize { 
+            return Err(StorageError::StorageOverflow { 
+                available: storage_end.saturating_sub(free_pos_usize).min(u16::MAX as usize),
+                needed: total_size,
+            }); 
         }
         
-        end_addr as *const u32
+        // Écrire le nom du fichier (avec null terminator)
+        let name_pos = free_pos.add(2);
+        memcpy(name_pos, filename_cstr.as_ptr(), filename_len);
+        
+        // Écrire le contenu
+        let content_pos = name_pos.add(filename_len);
+        memcpy(content_pos, content.as_ptr(), content_len);
+        
+        // Nettoyer le reste (marquer la fin des enregistrements)
+        let cleanup_pos = content_pos.add(content_len);
+        let cleanup_size = (storage_end as *mut u8).offset_from(cleanup_pos) as usize;
+        memset(cleanup_pos, 0, cleanup_size);
+
+        // Écrire le header (taille totale sur 2 bytes) en dernier : l'enregistrement devient visible
+        ptr::write_unaligned(free_pos as *mut u16, total_size as u16);
+        
+        Ok(())
     }
 }
 
-/// Vérifie si le stockage est valide (magic number)
-#[cfg(target_os = "none")]
-unsafe fn is_valid(addr: *const u32) -> Result<()> {
-    let magic_expected = 0xBADD0BEEu32.swap_bytes();
-    let magic_found = unsafe { ptr::read_unaligned(addr) };
-    if magic_found == magic_expected {
+/// Retire un enregistrement en décalant les suivants pour combler le trou
+unsafe fn erase_record(record: RawRecord) -> Result<()> {
+    unsafe {
+        let next_free_pos = next_free()?;
+        let record_end = record.start.add(record.size);
+        let move_size = next_free_pos.offset_from(record_end) as usize;
+        memmove(record.start, record_end, move_size);
+        
+        // Nettoyer l'espace libéré
+        memset(next_free_pos.sub(record.size), 0, record.size);
         Ok(())
-    } else {
-        Err(StorageError::InvalidMagicNumber { expected: magic_expected, found: magic_found })
     }
 }
 
-/// Détecte le modèle de calculatrice et retourne l'adresse userland
-#[cfg(target_os = "none")]
-unsafe fn userland_address() -> u32 {
+/// Retourne un itérateur sur les enregistrements, après vérification du magic number
+unsafe fn raw_records() -> Result<RawRecords> {
     unsafe {
-        // Adresses des slots magic pour chaque modèle
-        let slots_n0110 = [0x90010000 as *const u32, 0x90410000 as *const u32];
-        let slots_n0120 = [0x90020000 as *const u32, 0x90420000 as *const u32];
-        let magic = 0xfeedc0deu32.swap_bytes();
-        
-        // Compter les slots valides pour chaque modèle
-        let count_n0110 = slots_n0110.iter().filter(|&&slot| ptr::read_unaligned(slot) == magic).count();
-        let count_n0120 = slots_n0120.iter().filter(|&&slot| ptr::read_unaligned(slot) == magic).count();
-        
-        // Choisir l'adresse de base selon le modèle détecté
-        let base_addr = if count_n0110 > count_n0120 {
-            ptr::read_unaligned(0x20000004 as *const u32).wrapping_add(0x10000) // N0110
+        let storage_addr = address();
+        is_valid(storage_addr as *const u32)?;
+
+        Ok(RawRecords {
+            base: storage_addr as *mut u8,
+            offset: (storage_addr as *mut u8).add(4), // Skip magic number
+            end: (storage_addr + size()) as *mut u8,
+            problem: None,
+        })
+    }
+}
+
+
+// ============================================================================
+// SAFE API
+// ============================================================================
+
+// Indique si un handle `Storage` est actuellement actif
+#[cfg(target_os = "none")]
+static STORAGE_TAKEN: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+/// Réserve le stockage, retourne `false` s'il est déjà réservé
+#[cfg(target_os = "none")]
+fn lock_acquire() -> bool {
+    use core::sync::atomic::Ordering;
+    !STORAGE_TAKEN.swap(true, Ordering::Acquire)
+}
+
+/// Libère le stockage
+#[cfg(target_os = "none")]
+fn lock_release() {
+    use core::sync::atomic::Ordering;
+    STORAGE_TAKEN.store(false, Ordering::Release);
+}
+
+// Sur l'hôte, le stockage émulé est propre à chaque thread, le verrou aussi
+#[cfg(not(target_os = "none"))]
+std::thread_local! {
+    static STORAGE_TAKEN: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+#[cfg(not(target_os = "none"))]
+fn lock_acquire() -> bool {
+    !STORAGE_TAKEN.with(|taken| taken.replace(true))
+}
+
+#[cfg(not(target_os = "none"))]
+fn lock_release() {
+    STORAGE_TAKEN.with(|taken| taken.set(false));
+}
+
+/// # Handle exclusif sur le stockage
+///
+/// Un seul handle peut exister à la fois. Les vues empruntées ([`Storage::get`],
+/// [`Storage::get_str`]) pointent directement dans le stockage et vivent aussi
+/// longtemps que l'emprunt du handle : toute modification (écriture,
+/// suppression) demande `&mut self`, donc le compilateur refuse qu'une vue
+/// survive à une opération qui déplace les enregistrements.
+///
+/// Tant qu'un handle est actif, les fonctions libres ([`read`], [`write`], ...)
+/// retournent [`StorageError::Locked`].
+///
+/// ## Exemple
+/// ```rust,ignore
+/// let mut storage = Storage::take().unwrap();
+/// storage.write("score.txt", b"42")?;
+/// let score: &[u8] = storage.get("score.txt")?;
+/// // storage.erase("score.txt"); // Erreur de compilation : `score` emprunte encore `storage`
+/// ```
+pub struct Storage {
+    // Le handle est lié au stockage (et, sur l'hôte, au thread) qui l'a créé
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Storage {
+    /// Réserve le stockage. Retourne `None` si un autre handle est déjà actif.
+    pub fn take() -> Option<Self> {
+        if lock_acquire() {
+            Some(Storage { _not_send: PhantomData })
         } else {
-            ptr::read_unaligned(0x24000004 as *const u32).wrapping_add(0x20000) // N0120
-        };
-        
-        base_addr.wrapping_sub(0x8)
+            None
+        }
+    }
+
+    /// Retourne le contenu d'un fichier, sans copie
+    pub fn get(&self, filename: &str) -> Result<&[u8]> {
+        let (content_ptr, content_len) = unsafe { file_read_raw(filename)? };
+        Ok(unsafe { core::slice::from_raw_parts(content_ptr, content_len) })
+    }
+
+    /// Retourne le contenu d'un fichier texte (écrit avec [`file_write_string`]), sans copie
+    pub fn get_str(&self, filename: &str) -> Result<&str> {
+        let content = self.get(filename)?;
+
+        // Le contenu doit se terminer par le null terminator
+        match content.split_last() {
+            Some((0, text)) => core::str::from_utf8(text).map_err(|_| StorageError::InvalidInput),
+            _ => Err(StorageError::InvalidInput),
+        }
+    }
+
+    /// Vérifie si un fichier existe
+    pub fn exists(&self, filename: &str) -> bool {
+        file_exists(filename)
+    }
+
+    /// Retourne un itérateur sur les fichiers du stockage, sans copie
+    pub fn iter(&self) -> Result<Iter<'_>> {
+        Ok(Iter { records: unsafe { raw_records()? }, _storage: PhantomData })
+    }
+
+    /// Retourne les statistiques d'occupation du stockage
+    pub fn stats(&self) -> Result<StorageStats> {
+        let mut records = unsafe { raw_records()? };
+        let mut record_count = 0;
+        let mut largest: Option<Entry<'_>> = None;
+
+        for record in records.by_ref() {
+            record_count += 1;
+            if let Some(name) = unsafe { record.name() } {
+                let (_, size) = unsafe { record.content() };
+                if largest.is_none_or(|largest| size > largest.size) {
+                    largest = Some(Entry { name, size });
+                }
+            }
+        }
+
+        // Les enregistrements sont toujours compactés : tout l'espace libre est contigu, après le dernier
+        let total = unsafe { size() };
+        let used = records.end_of_chain()? as usize - unsafe { address() };
+
+        Ok(StorageStats {
+            total,
+            used,
+            free: total - used,
+            record_count,
+            largest_file: largest.map(FileInfo::from),
+        })
+    }
+
+    /// Écrit un fichier dans le stockage, en remplaçant son contenu s'il existe déjà (voir [`file_replace`])
+    pub fn write(&mut self, filename: &str, content: &[u8]) -> Result<()> {
+        unsafe { file_replace(filename, content) }
+    }
+
+    /// Renomme un fichier du stockage (voir [`file_rename`])
+    pub fn rename(&mut self, old_filename: &str, new_filename: &str) -> Result<()> {
+        unsafe { file_rename(old_filename, new_filename) }
+    }
+
+    /// Supprime un fichier du stockage
+    pub fn erase(&mut self, filename: &str) -> Result<()> {
+        unsafe { file_erase(filename) }
+    }
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        lock_release();
+    }
+}
+
+/// Sépare un nom de fichier en (nom, extension) sur le dernier point
+fn split_extension(filename: &str) -> (&str, Option<&str>) {
+    match filename.rsplit_once('.') {
+        Some((stem, extension)) => (stem, Some(extension)),
+        None => (filename, None),
+    }
+}
+
+/// Fichier du stockage, emprunté à un handle [`Storage`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry<'a> {
+    /// Nom complet du fichier, extension comprise (ex: `"script.py"`)
+    pub name: &'a str,
+    /// Taille du contenu en octets
+    pub size: usize,
+}
+
+impl<'a> Entry<'a> {
+    /// Nom du fichier sans l'extension
+    pub fn stem(&self) -> &'a str {
+        split_extension(self.name).0
+    }
+
+    /// Extension du fichier, sans le point (ex: `"py"`)
+    pub fn extension(&self) -> Option<&'a str> {
+        split_extension(self.name).1
+    }
+}
+
+/// Itérateur sur les fichiers du stockage, voir [`Storage::iter`]
+pub struct Iter<'a> {
+    records: RawRecords,
+    _storage: PhantomData<&'a Storage>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Entry<'a>;
+
+    fn next(&mut self) -> Option<Entry<'a>> {
+        for record in self.records.by_ref() {
+            // Ignorer les enregistrements dont le nom n'est pas en UTF-8
+            if let Some(name) = unsafe { record.name() } {
+                let (_, size) = unsafe { record.content() };
+                return Some(Entry { name, size });
+            }
+        }
+        None
     }
-}
\ No newline at end of file
+}
+
+/// Fichier du stockage (copie indépendante du stockage)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    /// Nom complet du fichier, extension comprise (ex: `"script.py"`)
+    pub name: String,
+    /// Taille du contenu en octets
+    pub size: usize,
+}
+
+impl FileInfo {
+    /// Nom du fichier sans l'extension
+    pub fn stem(&self) -> &str {
+        split_extension(&self.name).0
+    }
+
+    /// Extension du fichier, sans le point (ex: `"py"`)
+    pub fn extension(&self) -> Option<&str> {
+        split_extension(&self.name).1
+    }
+}
+
+impl From<Entry<'_>> for FileInfo {
+    fn from(entry: Entry<'_>) -> Self {
+        FileInfo { name: entry.name.to_string(), size: entry.size }
+    }
+}
+
+/// Liste les fichiers du stockage
+pub fn list() -> Result<Vec<FileInfo>> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    Ok(storage.iter()?.map(FileInfo::from).collect())
+}
+
+/// Liste les fichiers du stockage ayant l'extension donnée (sans le point, ex: `"py"`)
+pub fn list_with_extension(extension: &str) -> Result<Vec<FileInfo>> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    Ok(storage.iter()?
+        .filter(|entry| entry.extension() == Some(extension))
+        .map(FileInfo::from)
+        .collect())
+}
+
+/// Retourne un itérateur sur une copie de la liste des fichiers.
+///
+/// Le stockage n'est pas réservé pendant l'itération : les fichiers peuvent
+/// être lus ou modifiés dans la boucle. Pour parcourir le stockage sans copie,
+/// voir [`Storage::iter`].
+pub fn iter() -> Result<impl Iterator<Item = FileInfo>> {
+    Ok(list()?.into_iter())
+}
+
+/// Statistiques d'occupation du stockage, voir [`stats`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Taille totale du stockage en octets (magic number compris)
+    pub total: usize,
+    /// Octets occupés (magic number et enregistrements)
+    pub used: usize,
+    /// Octets libres. L'espace libre est toujours d'un seul bloc, il n'y a pas de fragmentation.
+    pub free: usize,
+    /// Nombre d'enregistrements (fichiers)
+    pub record_count: usize,
+    /// Plus gros fichier du stockage (taille du contenu), `None` si le stockage est vide
+    pub largest_file: Option<FileInfo>,
+}
+
+impl StorageStats {
+    /// Taille maximale du contenu d'un nouveau fichier nommé `filename`
+    pub fn max_content_size(&self, filename: &str) -> usize {
+        let overhead = 2 + filename.len() + 1; // taille_header + nom + null terminator
+        self.free.min(u16::MAX as usize).saturating_sub(overhead)
+    }
+
+    /// Vérifie qu'un nouveau fichier de `content_size` octets nommé `filename` tient dans l'espace libre.
+    /// 
+    /// Pour [`file_replace`], l'ancienne version n'est retirée qu'après l'écriture :
+    /// la même vérification s'applique.
+    pub fn can_write(&self, filename: &str, content_size: usize) -> bool {
+        content_size <= self.max_content_size(filename)
+    }
+}
+
+/// Retourne les statistiques d'occupation du stockage (taille totale, utilisée, libre, nombre de fichiers et plus gros fichier)
+pub fn stats() -> Result<StorageStats> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    storage.stats()
+}
+
+/// Lit un fichier et retourne une copie de son contenu
+pub fn read(filename: &str) -> Result<Vec<u8>> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    Ok(storage.get(filename)?.to_vec())
+}
+
+/// Copie le contenu d'un fichier dans `buffer` et retourne le nombre d'octets lus
+pub fn read_into(filename: &str, buffer: &mut [u8]) -> Result<usize> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    let content = storage.get(filename)?;
+
+    if content.len() > buffer.len() {
+        return Err(StorageError::BufferTooSmall { needed: content.len() });
+    }
+
+    buffer[..content.len()].copy_from_slice(content);
+    Ok(content.len())
+}
+
+/// Écrit un fichier dans le stockage, en remplaçant son contenu s'il existe déjà (voir [`file_replace`])
+pub fn write(filename: &str, content: &[u8]) -> Result<()> {
+    let mut storage = Storage::take().ok_or(StorageError::Locked)?;
+    storage.write(filename, content)
+}
+
+/// Renomme un fichier du stockage (voir [`file_rename`])
+pub fn rename(old_filename: &str, new_filename: &str) -> Result<()> {
+    let mut storage = Storage::take().ok_or(StorageError::Locked)?;
+    storage.rename(old_filename, new_filename)
+}
+
+
+// ============================================================================
+// FILE HANDLES
+// ============================================================================
+
+/// Mode d'ouverture d'un [`File`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Lecture seule, le fichier doit exister
+    Read,
+    /// Écriture depuis un contenu vide, le fichier est créé ou remplacé à la fermeture
+    Write,
+    /// Écriture à la suite du contenu existant (fichier créé s'il n'existe pas)
+    Append,
+}
+
+/// # Fichier ouvert dans le stockage
+/// 
+/// Implémente les traits [`embedded_io::Read`], [`embedded_io::Write`] et
+/// [`embedded_io::Seek`] : un parser, un logger ou postcard (`postcard::to_eio`
+/// et `postcard::from_eio`, feature `embedded-io-06` activée par eadkp) peut
+/// lire ou écrire directement dans le stockage.
+/// 
+/// En lecture, les données sont lues sans copie dans l'enregistrement.
+/// 
+/// En écriture, les données sont placées directement dans l'espace libre,
+/// après la fin de la chaîne d'enregistrements, sans `Vec` intermédiaire.
+/// Le fichier n'est visible qu'une fois validé par [`File::close`] (ou au
+/// `Drop`), avec les mêmes garanties que [`file_replace`] : l'ancienne version
+/// reste lisible jusqu'à ce que la nouvelle soit complète.
+/// 
+/// Le fichier réserve le stockage (comme un handle [`Storage`]) tant qu'il est ouvert.
+/// 
+/// ## Exemple
+/// ```rust,ignore
+/// use embedded_io::Write;
+/// 
+/// let mut log = storage::File::open("log.txt", storage::Mode::Append)?;
+/// log.write_all(b"Partie terminee\n")?;
+/// log.close()?;
+/// ```
+pub struct File {
+    _storage: Storage,
+    mode: Mode,
+    /// Nom du fichier (C string, avec null terminator)
+    filename: heapless::Vec<u8, 256>,
+    /// Lecture : contenu de l'enregistrement. Écriture : zone de préparation après la fin de la chaîne.
+    data: *mut u8,
+    /// Taille du contenu
+    len: usize,
+    /// Taille maximale du contenu (écriture)
+    capacity: usize,
+    /// Position de lecture/écriture
+    position: usize,
+    /// Le contenu a déjà été validé (ou n'a pas à l'être)
+    committed: bool,
+}
+
+impl File {
+    /// Ouvre un fichier du stockage
+    pub fn open(filename: &str, mode: Mode) -> Result<File> {
+        let storage = Storage::take().ok_or(StorageError::Locked)?;
+        let filename_cstr = to_cstring(filename)?;
+
+        unsafe {
+            let existing = find_record(filename_cstr.as_ptr())?;
+
+            if mode == Mode::Read {
+                let record = existing.ok_or(StorageError::FileNotFound)?;
+                let (data, len) = record.content();
+
+                return Ok(File {
+                    _storage: storage,
+                    mode,
+                    filename: filename_cstr,
+                    data: data as *mut u8,
+                    len,
+                    capacity: len,
+                    position: 0,
+                    committed: true,
+                });
+            }
+
+            // Zone de préparation : après la place du header et du nom du futur enregistrement
+            let free_pos = next_free()?;
+            let storage_end = (address() + size()) as *mut u8;
+            let available = storage_end.offset_from(free_pos) as usize;
+            let header_size = 2 + filename_cstr.len();
+
+            if available < header_size {
+                return Err(StorageError::StorageOverflow { available, needed: header_size });
+            }
+
+            let data = free_pos.add(header_size);
+            let capacity = (available - header_size).min(u16::MAX as usize - header_size);
+
+            // En ajout, repartir d'une copie du contenu actuel
+            let mut len = 0;
+            if let (Mode::Append, Some(record)) = (mode, existing) {
+                let (content_ptr, content_len) = record.content();
+                if content_len > capacity {
+                    return Err(StorageError::StorageOverflow { available: capacity, needed: content_len });
+                }
+                memcpy(data, content_ptr, content_len);
+                len = content_len;
+            }
+
+            Ok(File {
+                _storage: storage,
+                mode,
+                filename: filename_cstr,
+                data,
+                len,
+                capacity,
+                position: len,
+                committed: false,
+            })
+        }
+    }
+
+    /// Mode d'ouverture du fichier
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Taille actuelle du contenu
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indique si le contenu est vide
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Valide le contenu écrit et ferme le fichier, en retournant l'éventuelle erreur
+    /// 
+    /// Sans appel à `close`, la validation a lieu au `Drop` et l'erreur est ignorée.
+    pub fn close(mut self) -> Result<()> {
+        self.commit()
+    }
+
+    /// Ferme le fichier en abandonnant le contenu écrit : l'ancienne version est conservée
+    pub fn discard(mut self) {
+        self.committed = true;
+    }
+
+    /// Rend le contenu préparé visible en écrivant le header, puis retire l'ancienne version
+    fn commit(&mut self) -> Result<()> {
+        if self.committed {
+            return Ok(());
+        }
+        self.committed = true;
+
+        unsafe {
+            let header_size = 2 + self.filename.len();
+            let record_start = self.data.sub(header_size);
+
+            // Tant que le header n'est pas écrit, seule l'ancienne version est visible
+            let old_record = find_record(self.filename.as_ptr())?;
+
+            // Écrire le nom, puis nettoyer le reste du stockage (marquer la fin des enregistrements)
+            memcpy(record_start.add(2), self.filename.as_ptr(), self.filename.len());
+            let cleanup_pos = self.data.add(self.len);
+            let storage_end = (address() + size()) as *mut u8;
+            memset(cleanup_pos, 0, storage_end.offset_from(cleanup_pos) as usize);
+
+            // Écrire le header en dernier : l'enregistrement devient visible
+            ptr::write_unaligned(record_start as *mut u16, (header_size + self.len) as u16);
+
+            if let Some(record) = old_record {
+                erase_record(record)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        let _ = self.commit();
+    }
+}
+
+impl embedded_io::Error for StorageError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        use embedded_io::ErrorKind;
+
+        match self {
+            StorageError::FileNotFound => ErrorKind::NotFound,
+            StorageError::FileAlreadyExists => ErrorKind::AlreadyExists,
+            StorageError::InvalidInput => ErrorKind::InvalidInput,
+            StorageError::InsufficientSpace
+            | StorageError::StorageFull
+            | StorageError::StorageOverflow { .. }
+            | StorageError::BufferTooSmall { .. } => ErrorKind::OutOfMemory,
+            StorageError::InvalidStorage
+            | StorageError::InvalidMagicNumber { .. }
+            | StorageError::CorruptedStorage { .. }
+            | StorageError::DecodeError
+            | StorageError::VersionMismatch { .. }
+            | StorageError::ChecksumMismatch { .. } => ErrorKind::InvalidData,
+            StorageError::Locked | StorageError::SerializationError => ErrorKind::Other,
+        }
+    }
+}
+
+impl embedded_io::ErrorType for File {
+    type Error = StorageError;
+}
+
+impl embedded_io::Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.mode != Mode::Read {
+            return Err(StorageError::InvalidInput);
+        }
+
+        let count = buf.len().min(self.len - self.position);
+        unsafe { memcpy(buf.as_mut_ptr(), self.data.add(self.position), count) };
+        self.position += count;
+        Ok(count)
+    }
+}
+
+impl embedded_io::Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.mode == Mode::Read {
+            return Err(StorageError::InvalidInput);
+        }
+
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // Écriture partielle si la place manque, erreur seulement quand il n'y en a plus du tout
+        let count = buf.len().min(self.capacity - self.position);
+        if count == 0 {
+            return Err(StorageError::StorageOverflow { available: 0, needed: buf.len() });
+        }
+
+        unsafe { memcpy(self.data.add(self.position), buf.as_ptr(), count) };
+        self.position += count;
+        self.len = self.len.max(self.position);
+        Ok(count)
+    }
+
+    /// Les données sont déjà dans le stockage : la validation se fait à la fermeture
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl embedded_io::Seek for File {
+    /// Déplace la position de lecture/écriture, sans pouvoir dépasser la fin du contenu
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64> {
+        use embedded_io::SeekFrom;
+
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset as i64),
+            SeekFrom::End(offset) => (self.len as i64).checked_add(offset),
+            SeekFrom::Current(offset) => (self.position as i64).checked_add(offset),
+        };
+
+        match position {
+            Some(position) if (0..=self.len as i64).contains(&position) => {
+                self.position = position as usize;
+                Ok(self.position as u64)
+            }
+            _ => Err(StorageError::InvalidInput),
+        }
+    }
+}
+
+
+// ============================================================================
+// SERIALIZATION
+// ============================================================================
+
+/// Magic number des sauvegardes typées (`"ESV1"` en ASCII)
+pub const SAVE_MAGIC_NUMBER: u32 = u32::from_le_bytes(*b"ESV1");
+
+/// Version de schéma utilisée par [`save`] et [`load`]
+pub const DEFAULT_SCHEMA_VERSION: u16 = 0;
+
+/// Taille de l'en-tête d'une sauvegarde : \[4 bytes magic\] \[2 bytes version\] \[4 bytes checksum\]
+const SAVE_HEADER_SIZE: usize = 10;
+
+/// CRC-32 (IEEE 802.3), calculé bit à bit pour ne pas embarquer de table en flash
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB88320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Sérialise `value` avec postcard et l'enregistre dans un fichier (créé ou remplacé)
+/// 
+/// Équivaut à [`save_versioned`] avec [`DEFAULT_SCHEMA_VERSION`].
+pub fn save<T: Serialize>(filename: &str, value: &T) -> Result<()> {
+    save_versioned(filename, DEFAULT_SCHEMA_VERSION, value)
+}
+
+/// Charge une valeur enregistrée avec [`save`]
+pub fn load<T: DeserializeOwned>(filename: &str) -> Result<T> {
+    load_versioned(filename, DEFAULT_SCHEMA_VERSION)
+}
+
+/// Sérialise `value` avec postcard et l'enregistre dans un fichier (créé ou remplacé, voir [`file_replace`])
+/// 
+/// Format: \[4 bytes magic `ESV1`\] \[2 bytes version\] \[4 bytes CRC-32 des données\] \[données postcard\]
+/// 
+/// `version` identifie le schéma de `T` : l'incrémenter à chaque modification
+/// de la structure permet à [`load_versioned`] de refuser les anciennes sauvegardes
+/// au lieu de les décoder de travers.
+pub fn save_versioned<T: Serialize>(filename: &str, version: u16, value: &T) -> Result<()> {
+    // Réserver la place de l'en-tête puis sérialiser directement à la suite
+    let header = [0u8; SAVE_HEADER_SIZE];
+    let mut content = postcard::to_extend(value, header.to_vec())
+        .map_err(|_| StorageError::SerializationError)?;
+
+    let checksum = crc32(&content[SAVE_HEADER_SIZE..]);
+    content[0..4].copy_from_slice(&SAVE_MAGIC_NUMBER.to_le_bytes());
+    content[4..6].copy_from_slice(&version.to_le_bytes());
+    content[6..10].copy_from_slice(&checksum.to_le_bytes());
+
+    write(filename, &content)
+}
+
+/// Charge une valeur enregistrée avec [`save_versioned`], en vérifiant la version et le checksum
+pub fn load_versioned<T: DeserializeOwned>(filename: &str, version: u16) -> Result<T> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    let content = storage.get(filename)?;
+
+    if content.len() < SAVE_HEADER_SIZE {
+        return Err(StorageError::DecodeError);
+    }
+
+    let (header, data) = content.split_at(SAVE_HEADER_SIZE);
+    let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let found_version = u16::from_le_bytes([header[4], header[5]]);
+    let expected_checksum = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+
+    if magic != SAVE_MAGIC_NUMBER {
+        return Err(StorageError::DecodeError);
+    }
+
+    if found_version != version {
+        return Err(StorageError::VersionMismatch { expected: version, found: found_version });
+    }
+
+    let checksum = crc32(data);
+    if checksum != expected_checksum {
+        return Err(StorageError::ChecksumMismatch { expected: expected_checksum, found: checksum });
+    }
+
+    postcard::from_bytes(data).map_err(|_| StorageError::DecodeError)
+}
+
+
+// ============================================================================
+// SETTINGS
+// ============================================================================
+
+/// Magic number des fichiers de réglages (`"EST1"` en ASCII)
+pub const SETTINGS_MAGIC_NUMBER: u32 = u32::from_le_bytes(*b"EST1");
+
+/// Valeur d'un réglage, voir [`Settings`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingValue {
+    Int(i64),
+    Bool(bool),
+    /// Chaîne courte (65535 octets maximum)
+    Str(String),
+    /// Données brutes (65535 octets maximum)
+    Bytes(Vec<u8>),
+}
+
+impl SettingValue {
+    const TAG_INT: u8 = 0;
+    const TAG_BOOL: u8 = 1;
+    const TAG_STR: u8 = 2;
+    const TAG_BYTES: u8 = 3;
+}
+
+impl From<i64> for SettingValue {
+    fn from(value: i64) -> Self { SettingValue::Int(value) }
+}
+
+impl From<i32> for SettingValue {
+    fn from(value: i32) -> Self { SettingValue::Int(value as i64) }
+}
+
+impl From<u32> for SettingValue {
+    fn from(value: u32) -> Self { SettingValue::Int(value as i64) }
+}
+
+impl From<bool> for SettingValue {
+    fn from(value: bool) -> Self { SettingValue::Bool(value) }
+}
+
+impl From<&str> for SettingValue {
+    fn from(value: &str) -> Self { SettingValue::Str(value.to_string()) }
+}
+
+impl From<String> for SettingValue {
+    fn from(value: String) -> Self { SettingValue::Str(value) }
+}
+
+impl From<&[u8]> for SettingValue {
+    fn from(value: &[u8]) -> Self { SettingValue::Bytes(value.to_vec()) }
+}
+
+impl From<Vec<u8>> for SettingValue {
+    fn from(value: Vec<u8>) -> Self { SettingValue::Bytes(value) }
+}
+
+/// # Réglages clé/valeur stockés dans un seul fichier
+/// 
+/// Regroupe les petits réglages d'une application (meilleur score, volume,
+/// difficulté, ...) dans un seul enregistrement au lieu d'un fichier chacun.
+/// 
+/// Le fichier est chargé au premier accès, les modifications restent en
+/// mémoire jusqu'à [`Settings::commit`], qui remplace le fichier d'un coup
+/// (voir [`file_replace`]).
+/// 
+/// Format: \[4 bytes magic `EST1`\] \[2 bytes nombre d'entrées\] puis pour chaque entrée
+/// \[1 byte taille clé\] \[clé\] \[1 byte type\] \[valeur\]. Les chaînes et données
+/// brutes sont précédées de leur taille sur 2 bytes.
+/// 
+/// ## Exemple
+/// ```rust,ignore
+/// let mut settings = storage::Settings::new("bounce.set");
+/// let best = settings.get_int("best")?.unwrap_or(0);
+/// settings.set("best", best.max(score))?;
+/// settings.set("sound", true)?;
+/// settings.commit()?;
+/// ```
+pub struct Settings {
+    filename: String,
+    /// Entrées chargées, `None` tant que le fichier n'a pas été lu
+    entries: Option<Vec<(String, SettingValue)>>,
+    /// Des modifications n'ont pas encore été enregistrées
+    dirty: bool,
+}
+
+impl Settings {
+    /// Crée les réglages associés à un fichier, sans le lire
+    pub fn new(filename: &str) -> Self {
+        Settings { filename: filename.to_string(), entries: None, dirty: false }
+    }
+
+    /// Nom du fichier de réglages
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Indique si des modifications n'ont pas encore été enregistrées avec [`Settings::commit`]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Charge les entrées au premier accès (un fichier absent donne des réglages vides)
+    fn entries(&mut self) -> Result<&mut Vec<(String, SettingValue)>> {
+        if self.entries.is_none() {
+            let entries = match read(&self.filename) {
+                Ok(content) => decode_settings(&content)?,
+                Err(StorageError::FileNotFound) => Vec::new(),
+                Err(error) => return Err(error),
+            };
+            self.entries = Some(entries);
+        }
+
+        Ok(self.entries.get_or_insert_with(Vec::new))
+    }
+
+    /// Retourne la valeur d'un réglage
+    pub fn get(&mut self, key: &str) -> Result<Option<&SettingValue>> {
+        Ok(self.entries()?.iter().find(|(k, _)| k == key).map(|(_, value)| value))
+    }
+
+    /// Retourne un réglage entier, `None` s'il est absent ou d'un autre type
+    pub fn get_int(&mut self, key: &str) -> Result<Option<i64>> {
+        Ok(match self.get(key)? {
+            Some(SettingValue::Int(value)) => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Retourne un réglage booléen, `None` s'il est absent ou d'un autre type
+    pub fn get_bool(&mut self, key: &str) -> Result<Option<bool>> {
+        Ok(match self.get(key)? {
+            Some(SettingValue::Bool(value)) => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Retourne un réglage texte, `None` s'il est absent ou d'un autre type
+    pub fn get_str(&mut self, key: &str) -> Result<Option<&str>> {
+        Ok(match self.get(key)? {
+            Some(SettingValue::Str(value)) => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Retourne un réglage en données brutes, `None` s'il est absent ou d'un autre type
+    pub fn get_bytes(&mut self, key: &str) -> Result<Option<&[u8]>> {
+        Ok(match self.get(key)? {
+            Some(SettingValue::Bytes(value)) => Some(value.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Définit un réglage (clé de 255 octets maximum)
+    pub fn set(&mut self, key: &str, value: impl Into<SettingValue>) -> Result<()> {
+        let value = value.into();
+
+        // Vérifier que l'entrée pourra être encodée
+        let too_long = match &value {
+            SettingValue::Str(text) => text.len() > u16::MAX as usize,
+            SettingValue::Bytes(bytes) => bytes.len() > u16::MAX as usize,
+            _ => false,
+        };
+        if key.len() > u8::MAX as usize || too_long {
+            return Err(StorageError::InvalidInput);
+        }
+
+        let entries = self.entries()?;
+        match entries.iter_mut().find(|(k, _)| k == key) {
+            Some((_, current)) if *current == value => return Ok(()),
+            Some((_, current)) => *current = value,
+            None => entries.push((key.to_string(), value)),
+        }
+
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Supprime un réglage et retourne son ancienne valeur
+    pub fn remove(&mut self, key: &str) -> Result<Option<SettingValue>> {
+        let entries = self.entries()?;
+        let removed = entries.iter()
+            .position(|(k, _)| k == key)
+            .map(|index| entries.remove(index).1);
+
+        if removed.is_some() {
+            self.dirty = true;
+        }
+        Ok(removed)
+    }
+
+    /// Enregistre les modifications dans le stockage (rien n'est écrit s'il n'y en a pas)
+    pub fn commit(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let content = encode_settings(self.entries()?);
+        write(&self.filename, &content)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Oublie les modifications non enregistrées, le fichier sera relu au prochain accès
+    pub fn reload(&mut self) {
+        self.entries = None;
+        self.dirty = false;
+    }
+}
+
+/// Encode les réglages (les tailles ont été vérifiées par [`Settings::set`])
+fn encode_settings(entries: &[(String, SettingValue)]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&SETTINGS_MAGIC_NUMBER.to_le_bytes());
+    content.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+    for (key, value) in entries {
+        content.push(key.len() as u8);
+        content.extend_from_slice(key.as_bytes());
+
+        match value {
+            SettingValue::Int(value) => {
+                content.push(SettingValue::TAG_INT);
+                content.extend_from_slice(&value.to_le_bytes());
+            }
+            SettingValue::Bool(value) => {
+                content.push(SettingValue::TAG_BOOL);
+                content.push(*value as u8);
+            }
+            SettingValue::Str(text) => {
+                content.push(SettingValue::TAG_STR);
+                content.extend_from_slice(&(text.len() as u16).to_le_bytes());
+                content.extend_from_slice(text.as_bytes());
+            }
+            SettingValue::Bytes(bytes) => {
+                content.push(SettingValue::TAG_BYTES);
+                content.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+                content.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    content
+}
+
+/// Décode les réglages, [`StorageError::DecodeError`] si le contenu est invalide
+fn decode_settings(content: &[u8]) -> Result<Vec<(String, SettingValue)>> {
+    // Lecteur minimal qui échoue au lieu de dépasser la fin du contenu
+    fn take<'a>(content: &mut &'a [u8], count: usize) -> Result<&'a [u8]> {
+        if content.len() < count {
+            return Err(StorageError::DecodeError);
+        }
+        let (taken, rest) = content.split_at(count);
+        *content = rest;
+        Ok(taken)
+    }
+
+    fn take_u16(content: &mut &[u8]) -> Result<u16> {
+        let bytes = take(content, 2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn take_str(content: &mut &[u8], len: usize) -> Result<String> {
+        let bytes = take(content, len)?;
+        core::str::from_utf8(bytes)
+            .map(|text| text.to_string())
+            .map_err(|_| StorageError::DecodeError)
+    }
+
+    let mut content = content;
+    let magic = take(&mut content, 4)?;
+    if magic != SETTINGS_MAGIC_NUMBER.to_le_bytes() {
+        return Err(StorageError::DecodeError);
+    }
+
+    let count = take_u16(&mut content)?;
+    let mut entries = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let key_len = take(&mut content, 1)?[0] as usize;
+        let key = take_str(&mut content, key_len)?;
+
+        let value = match take(&mut content, 1)?[0] {
+            SettingValue::TAG_INT => {
+                let bytes = take(&mut content, 8)?;
+                SettingValue::Int(i64::from_le_bytes(bytes.try_into().map_err(|_| StorageError::DecodeError)?))
+            }
+            SettingValue::TAG_BOOL => SettingValue::Bool(take(&mut content, 1)?[0] != 0),
+            SettingValue::TAG_STR => {
+                let len = take_u16(&mut content)? as usize;
+                SettingValue::Str(take_str(&mut content, len)?)
+            }
+            SettingValue::TAG_BYTES => {
+                let len = take_u16(&mut content)? as usize;
+                SettingValue::Bytes(take(&mut content, len)?.to_vec())
+            }
+            _ => return Err(StorageError::DecodeError),
+        };
+
+        entries.push((key, value));
+    }
+
+    Ok(entries)
+}
+
+
+// ============================================================================
+// VERIFICATION
+// ============================================================================
+
+/// Type de problème détecté sur un enregistrement, voir [`verify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordProblemKind {
+    /// Taille trop petite pour contenir le header et un nom (boucle infinie si `0 < size < 3`)
+    InvalidSize { size: u16 },
+    /// L'enregistrement dépasse la fin du stockage
+    OutOfBounds { size: u16, available: usize },
+    /// Le nom n'a pas de null terminator à l'intérieur de l'enregistrement
+    UnterminatedName,
+    /// Le nom n'est pas en UTF-8
+    InvalidName,
+    /// Un enregistrement suivant porte le même nom : version plus ancienne, la seule lisible
+    /// (ex: coupure pendant [`file_replace`]), que [`repair`] retire pour garder la plus récente
+    DuplicateName,
+}
+
+impl RecordProblemKind {
+    /// Indique si le problème casse la chaîne : les enregistrements suivants sont inaccessibles
+    pub fn breaks_chain(&self) -> bool {
+        matches!(
+            self,
+            RecordProblemKind::InvalidSize { .. }
+                | RecordProblemKind::OutOfBounds { .. }
+                | RecordProblemKind::UnterminatedName
+        )
+    }
+}
+
+/// Problème détecté sur un enregistrement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordProblem {
+    /// Position de l'enregistrement depuis le début du stockage (magic number compris)
+    pub offset: usize,
+    pub kind: RecordProblemKind,
+}
+
+/// Résultat de [`verify`] ou [`repair`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Nombre d'enregistrements lisibles avant la fin (ou la rupture) de la chaîne
+    pub records: usize,
+    /// Problèmes détectés, dans l'ordre de la chaîne
+    pub problems: Vec<RecordProblem>,
+}
+
+impl VerifyReport {
+    /// Indique si aucun problème n'a été détecté
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl Storage {
+    /// Vérifie l'intégrité de la chaîne d'enregistrements, sans rien modifier
+    /// 
+    /// Contrôle les limites et la taille de chaque enregistrement, la
+    /// terminaison et l'encodage des noms, et l'absence de doublons.
+    /// Retourne une erreur seulement si le magic number est invalide.
+    pub fn verify(&self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        unsafe {
+            let mut records = raw_records()?;
+            let base = records.base;
+            let chain: Vec<RawRecord> = records.by_ref().collect();
+            report.records = chain.len();
+
+            for (index, record) in chain.iter().enumerate() {
+                let offset = record.start.offset_from(base) as usize;
+
+                if record.name().is_none() {
+                    report.problems.push(RecordProblem { offset, kind: RecordProblemKind::InvalidName });
+                } else if chain[index + 1..].iter().any(|later| strcmp(later.name_ptr(), record.name_ptr())) {
+                    // Seule la dernière version d'un nom est gardée : c'est la plus récente
+                    report.problems.push(RecordProblem { offset, kind: RecordProblemKind::DuplicateName });
+                }
+            }
+
+            // Rupture de la chaîne (toujours le dernier problème)
+            if let Some(problem) = records.problem {
+                report.problems.push(problem);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Répare le stockage et retourne les problèmes corrigés
+    /// 
+    /// La chaîne est tronquée au premier enregistrement cassé (tout ce qui suit
+    /// est effacé), puis les enregistrements au nom invalide sont retirés. Pour
+    /// un nom en double, seul le dernier enregistrement complet (le plus récent)
+    /// est gardé. **Les données tronquées sont perdues.**
+    pub fn repair(&mut self) -> Result<VerifyReport> {
+        let report = self.verify()?;
+
+        unsafe {
+            let base = address() as *mut u8;
+            let end = base.add(size());
+
+            // Tronquer la chaîne : la zone effacée devient l'espace libre
+            if let Some(problem) = report.problems.iter().find(|problem| problem.kind.breaks_chain()) {
+                let cut = base.add(problem.offset);
+                memset(cut, 0, end.offset_from(cut) as usize);
+            }
+
+            // Retirer les enregistrements restants, de la fin vers le début pour garder les positions valides
+            for problem in report.problems.iter().rev().filter(|problem| !problem.kind.breaks_chain()) {
+                let start = base.add(problem.offset);
+                let size = ptr::read_unaligned(start as *const u16) as usize;
+                erase_record(RawRecord { start, size })?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Vérifie l'intégrité du stockage (voir [`Storage::verify`])
+pub fn verify() -> Result<VerifyReport> {
+    let storage = Storage::take().ok_or(StorageError::Locked)?;
+    storage.verify()
+}
+
+/// Tronque la chaîne au premier enregistrement corrompu et retire les noms invalides ou en double (voir [`Storage::repair`])
+pub fn repair() -> Result<VerifyReport> {
+    let mut storage = Storage::take().ok_or(StorageError::Locked)?;
+    storage.repair()
+}
+
+
+// ============================================================================
+// HARDWARE INTERFACE
+// ============================================================================
+
+/// Retourne l'adresse de base du stockage
+#[cfg(target_os = "none")]
+unsafe fn address() -> usize {
+    crate::system::userland().storage_address
+}
+
+/// Retourne la taille totale du stockage
+#[cfg(target_os = "none")]
+unsafe fn size() -> usize {
+    crate::system::userland().storage_size
+}
+
+/// Trouve la prochaine position libre dans le stockage
+unsafe fn next_free() -> Result<*mut u8> {
+    unsafe {
+        let mut records = raw_records()?;
+        
+        // Parcourir jusqu'à trouver un enregistrement vide (size=0)
+        for _ in records.by_ref() {}
+        records.end_of_chain()
+    }
+}
+
+/// Vérifie si le stockage est valide (magic number)
+unsafe fn is_valid(addr: *const u32) -> Result<()> {
+    let magic_expected = 0xBADD0BEEu32.swap_bytes();
+    let magic_found = unsafe { ptr::read_unaligned(addr) };
+    if magic_found == magic_expected {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidMagicNumber { expected: magic_expected, found: magic_found })
+    }
+}
+
+// ============================================================================
+// HOST BACKEND
+// ============================================================================
+
+/// Taille du stockage émulé hors calculatrice (même ordre de grandeur que sur l'appareil)
+#[cfg(not(target_os = "none"))]
+pub const HOST_STORAGE_SIZE: usize = 32 * 1024;
+
+/// Construit un stockage vierge : magic number suivi de zéros (aucun enregistrement)
+#[cfg(not(target_os = "none"))]
+const fn host_blank_storage() -> [u8; HOST_STORAGE_SIZE] {
+    let mut storage = [0u8; HOST_STORAGE_SIZE];
+    let magic = 0xBADD0BEEu32.swap_bytes().to_ne_bytes();
+    let mut i = 0;
+    while i < magic.len() {
+        storage[i] = magic[i];
+        i += 1;
+    }
+    storage
+}
+
+// Un stockage par thread : les tests lancés en parallèle par `cargo test` ne se marchent pas dessus
+#[cfg(not(target_os = "none"))]
+std::thread_local! {
+    static HOST_STORAGE: core::cell::UnsafeCell<[u8; HOST_STORAGE_SIZE]> =
+        const { core::cell::UnsafeCell::new(host_blank_storage()) };
+}
+
+/// Retourne l'adresse de base du stockage émulé
+#[cfg(not(target_os = "none"))]
+unsafe fn address() -> usize {
+    HOST_STORAGE.with(|storage| storage.get() as usize)
+}
+
+/// Retourne la taille totale du stockage émulé
+#[cfg(not(target_os = "none"))]
+unsafe fn size() -> usize {
+    HOST_STORAGE_SIZE
+}
+
+/// Adresse et taille du stockage émulé du thread courant (voir `system::userland`)
+#[cfg(not(target_os = "none"))]
+pub(crate) fn host_region() -> (usize, usize) {
+    unsafe { (address(), size()) }
+}
+
+/// Réinitialise le stockage émulé du thread courant (tous les fichiers sont supprimés)
+#[cfg(not(target_os = "none"))]
+pub fn host_reset() {
+    HOST_STORAGE.with(|storage| unsafe { *storage.get() = host_blank_storage() });
+}
@@ -0,0 +1,98 @@
+/// Calculator model, detected from the layout of the external flash slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    N0110,
+    /// The N0115 shares the N0110 flash layout: on a device it is reported as [`Model::N0110`].
+    /// This variant is only returned by a simulated model on host targets.
+    N0115,
+    N0120,
+    /// No valid slot was found. The storage falls back to the N0120 layout.
+    Unknown,
+}
+
+impl Model {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Model::N0110 => "N0110",
+            Model::N0115 => "N0115",
+            Model::N0120 => "N0120",
+            Model::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Userland layout of the running calculator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserlandInfo {
+    /// Detected calculator model
+    pub model: Model,
+    /// Address of the userland header (`0` on host targets)
+    pub address: usize,
+    /// Start address of the user storage (records area, magic number included)
+    pub storage_address: usize,
+    /// Size of the user storage in bytes
+    pub storage_size: usize,
+}
+
+/// Returns the detected calculator model.
+pub fn model() -> Model {
+    userland().model
+}
+
+/// Detects the calculator model and returns the userland layout.
+#[cfg(target_os = "none")]
+pub fn userland() -> UserlandInfo {
+    use core::ptr;
+
+    unsafe {
+        // Slot magic addresses for each model
+        let slots_n0110 = [0x90010000 as *const u32, 0x90410000 as *const u32];
+        let slots_n0120 = [0x90020000 as *const u32, 0x90420000 as *const u32];
+        let magic = 0xfeedc0deu32.swap_bytes();
+
+        // Count the valid slots for each model
+        let count_n0110 = slots_n0110.iter().filter(|&&slot| ptr::read_unaligned(slot) == magic).count();
+        let count_n0120 = slots_n0120.iter().filter(|&&slot| ptr::read_unaligned(slot) == magic).count();
+
+        // Pick the base address of the detected model
+        let (model, base_addr) = if count_n0110 > count_n0120 {
+            (Model::N0110, ptr::read_unaligned(0x20000004 as *const u32).wrapping_add(0x10000))
+        } else {
+            let model = if count_n0120 > 0 { Model::N0120 } else { Model::Unknown };
+            (model, ptr::read_unaligned(0x24000004 as *const u32).wrapping_add(0x20000))
+        };
+
+        let address = base_addr.wrapping_sub(0x8);
+
+        UserlandInfo {
+            model,
+            address: address as usize,
+            storage_address: ptr::read_unaligned((address + 0xC) as *const u32) as usize,
+            storage_size: ptr::read_unaligned((address + 0x10) as *const u32) as usize,
+        }
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+std::thread_local! {
+    static SIMULATED_MODEL: core::cell::Cell<Model> = const { core::cell::Cell::new(Model::N0120) };
+}
+
+/// Returns the simulated userland layout: the configured model and the in-memory storage.
+#[cfg(not(target_os = "none"))]
+pub fn userland() -> UserlandInfo {
+    let (storage_address, storage_size) = crate::storage::host_region();
+
+    UserlandInfo {
+        model: SIMULATED_MODEL.with(|model| model.get()),
+        address: 0,
+        storage_address,
+        storage_size,
+    }
+}
+
+/// Sets the model reported on host targets for the current thread (defaults to [`Model::N0120`]).
+#[cfg(not(target_os = "none"))]
+pub fn set_simulated_model(model: Model) {
+    SIMULATED_MODEL.with(|simulated| simulated.set(model));
+}
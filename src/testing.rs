@@ -0,0 +1,269 @@
+//! # Golden-image snapshot testing
+//!
+//! Host-only helpers (feature `testing`) to check the rendering of an app in `cargo test`.
+//! Drawing goes to the in-memory screen of [`display::host`], the screen is captured with
+//! [`display::pull_rect`] and compared pixel by pixel (RGB565) with a reference PNG.
+//!
+//! - The reference of `name` is `<snapshot dir>/<name>.png`. The snapshot dir is `$EADKP_SNAPSHOT_DIR`,
+//!   or `tests/snapshots` in the crate being tested.
+//! - A missing reference is written by the first run. Set `EADKP_UPDATE_SNAPSHOTS=1` to overwrite
+//!   the references after an intended change.
+//! - On mismatch, `<name>.actual.png` and `<name>.diff.png` are written next to the reference:
+//!   the diff shows matching pixels faded and differing pixels in red.
+//!
+//! ```rust,ignore
+//! use eadkp::{testing, display, Rect, COLOR_RED, COLOR_WHITE};
+//!
+//! #[test]
+//! fn menu_layout() {
+//!     testing::reset(COLOR_WHITE);
+//!     draw_menu(); // App code using `eadkp::display`
+//!     testing::assert_snapshot("menu");
+//! }
+//!
+//! #[test]
+//! fn menu_animation() {
+//!     let frames = testing::record(3, |frame| draw_menu_frame(frame));
+//!     for (index, frame) in frames.iter().enumerate() {
+//!         frame.assert_matches(&format!("menu_animation_{}", index));
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use crate::{display, Color, Rect, SCREEN_RECT};
+
+/// Environment variable overriding the snapshot directory
+pub const SNAPSHOT_DIR_VAR: &str = "EADKP_SNAPSHOT_DIR";
+/// Environment variable that makes every comparison overwrite its reference (`1` or `true`)
+pub const UPDATE_SNAPSHOTS_VAR: &str = "EADKP_UPDATE_SNAPSHOTS";
+
+/// Faded color of the matching pixels in a diff image
+const DIFF_FADE: u8 = 3;
+/// Color of the differing pixels in a diff image
+const DIFF_COLOR: (u8, u8, u8) = (255, 0, 0);
+
+// ==================================================================================
+// ===================================== ERRORS =====================================
+// ==================================================================================
+
+/// Failure of a snapshot comparison
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The reference couldn't be read or decoded, or the outputs couldn't be written
+    Io(std::io::Error),
+    /// The reference doesn't have the size of the capture
+    SizeMismatch { expected: (u16, u16), found: (u16, u16) },
+    /// Some pixels differ from the reference, the diff image was written to `diff`
+    Mismatch { differing: usize, bounds: Rect, diff: PathBuf },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "snapshot I/O error: {}", error),
+            SnapshotError::SizeMismatch { expected, found } => write!(
+                f,
+                "snapshot size mismatch: reference is {}x{}, capture is {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            SnapshotError::Mismatch { differing, bounds, diff } => write!(
+                f,
+                "{} pixels differ from the reference in the {}x{} zone at ({}, {}), see {}",
+                differing,
+                bounds.width,
+                bounds.height,
+                bounds.x,
+                bounds.y,
+                diff.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+// ==================================================================================
+// ==================================== SNAPSHOT ====================================
+// ==================================================================================
+
+/// Captured pixels of a zone of the screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub width: u16,
+    pub height: u16,
+    /// RGB565 pixels, row by row
+    pub pixels: Vec<Color>,
+}
+
+impl Snapshot {
+    /// Decodes a PNG file, converting its pixels to RGB565.
+    pub fn from_png(file: &[u8]) -> std::io::Result<Snapshot> {
+        let mut decoder = png::Decoder::new(Cursor::new(file));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or(0)];
+        let info = reader.next_frame(&mut buffer)?;
+
+        let channels = info.color_type.samples();
+        let pixels = buffer[..info.line_size * info.height as usize]
+            .chunks_exact(channels)
+            .map(|pixel| match info.color_type {
+                png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => Color::from_888(pixel[0], pixel[0], pixel[0]),
+                _ => Color::from_888(pixel[0], pixel[1], pixel[2]),
+            })
+            .collect();
+
+        Ok(Snapshot { width: info.width as u16, height: info.height as u16, pixels })
+    }
+
+    /// Encodes the snapshot as an 8-bit RGB PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        display::host::encode_png(&self.pixels, self.width as u32, self.height as u32)
+            .expect("Encoding an RGB image to memory can't fail")
+    }
+
+    /// Compares the snapshot with the reference `name` (see the [module documentation](self)).
+    pub fn check(&self, name: &str) -> Result<(), SnapshotError> {
+        let directory = snapshot_dir();
+        let reference_path = directory.join(format!("{}.png", name));
+
+        if updating() || !reference_path.exists() {
+            std::fs::create_dir_all(&directory)?;
+            std::fs::write(&reference_path, self.to_png())?;
+            return Ok(());
+        }
+
+        let reference = Snapshot::from_png(&std::fs::read(&reference_path)?)?;
+        if (reference.width, reference.height) != (self.width, self.height) {
+            std::fs::write(directory.join(format!("{}.actual.png", name)), self.to_png())?;
+            return Err(SnapshotError::SizeMismatch {
+                expected: (reference.width, reference.height),
+                found: (self.width, self.height),
+            });
+        }
+
+        let Some((diff, differing, bounds)) = self.diff(&reference) else {
+            return Ok(());
+        };
+
+        let diff_path = directory.join(format!("{}.diff.png", name));
+        std::fs::write(directory.join(format!("{}.actual.png", name)), self.to_png())?;
+        std::fs::write(&diff_path, diff.to_png())?;
+
+        Err(SnapshotError::Mismatch { differing, bounds, diff: diff_path })
+    }
+
+    /// Compares the snapshot with the reference `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the number and zone of the differing pixels if the snapshot doesn't match.
+    #[track_caller]
+    pub fn assert_matches(&self, name: &str) {
+        if let Err(error) = self.check(name) {
+            panic!("snapshot `{}` doesn't match: {}", name, error);
+        }
+    }
+
+    /// Returns the diff image, the number of differing pixels and their bounding box, `None` if both are identical.
+    pub fn diff(&self, reference: &Snapshot) -> Option<(Snapshot, usize, Rect)> {
+        let width = self.width as usize;
+        let (mut left, mut top, mut right, mut bottom) = (usize::MAX, usize::MAX, 0, 0);
+        let mut differing = 0;
+
+        let pixels = self
+            .pixels
+            .iter()
+            .zip(&reference.pixels)
+            .enumerate()
+            .map(|(index, (actual, expected))| {
+                if actual == expected {
+                    let (r, g, b) = actual.get_888();
+                    let fade = |channel: u8| 255 - (255 - channel) / DIFF_FADE;
+                    return Color::from_888(fade(r), fade(g), fade(b));
+                }
+
+                differing += 1;
+                let (x, y) = (index % width, index / width);
+                (left, top, right, bottom) = (left.min(x), top.min(y), right.max(x), bottom.max(y));
+                Color::from_888(DIFF_COLOR.0, DIFF_COLOR.1, DIFF_COLOR.2)
+            })
+            .collect();
+
+        if differing == 0 {
+            return None;
+        }
+
+        let bounds = Rect {
+            x: left as u16,
+            y: top as u16,
+            width: (right - left + 1) as u16,
+            height: (bottom - top + 1) as u16,
+        };
+        Some((Snapshot { width: self.width, height: self.height, pixels }, differing, bounds))
+    }
+}
+
+// ==================================================================================
+// ==================================== CAPTURE =====================================
+// ==================================================================================
+
+/// Clears the simulated screen with `color` before drawing a test scene.
+pub fn reset(color: Color) {
+    display::host::clear(color);
+}
+
+/// Captures the whole screen with [`display::pull_rect`].
+pub fn capture() -> Snapshot {
+    capture_rect(SCREEN_RECT)
+}
+
+/// Captures a zone of the screen with [`display::pull_rect`].
+pub fn capture_rect(rect: Rect) -> Snapshot {
+    Snapshot { width: rect.width, height: rect.height, pixels: display::pull_rect(rect) }
+}
+
+/// Runs `frames` frames of `draw` (called with the frame number) and captures the screen after each one.
+///
+/// Like an app main loop, every frame ends with [`display::wait_for_vblank`].
+pub fn record(frames: usize, mut draw: impl FnMut(usize)) -> Vec<Snapshot> {
+    (0..frames)
+        .map(|frame| {
+            draw(frame);
+            display::wait_for_vblank();
+            capture()
+        })
+        .collect()
+}
+
+/// Captures the whole screen and compares it with the reference `name`, see [`Snapshot::assert_matches`].
+#[track_caller]
+pub fn assert_snapshot(name: &str) {
+    capture().assert_matches(name);
+}
+
+/// Directory of the reference images
+pub fn snapshot_dir() -> PathBuf {
+    if let Some(directory) = std::env::var_os(SNAPSHOT_DIR_VAR) {
+        return PathBuf::from(directory);
+    }
+
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from).unwrap_or_default();
+    root.join("tests").join("snapshots")
+}
+
+/// Returns `true` if the references must be overwritten
+fn updating() -> bool {
+    std::env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+}
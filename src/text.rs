@@ -0,0 +1,236 @@
+//! # Bitmap font rendering
+//!
+//! Draws text with fonts converted at build time, beyond the two firmware
+//! fonts of `display::draw_string`. A font is a glyph atlas placed in
+//! `assets/fonts/<name>.png` with the list of its characters in
+//! `assets/fonts/<name>.txt` (see `docs/TECHNICALS/EFF/EFF1.md`).
+//!
+//! Glyphs have proportional widths, any UTF-8 character of the atlas can be
+//! drawn, the background can be transparent and the text can be scaled by an
+//! integer factor.
+//!
+//! ```rust,ignore
+//! use eadkp::{include_font, text::{Font, TextStyle}, Point, COLOR_BLACK, COLOR_WHITE};
+//!
+//! static PIXEL_FONT: Option<Font> = Font::from_raw(include_font!("pixel"));
+//!
+//! let font = PIXEL_FONT.unwrap();
+//! font.draw("Température: 20°C", Point { x: 10, y: 10 }, COLOR_BLACK);
+//! font.draw_styled("Score", Point { x: 10, y: 40 }, &TextStyle::new(COLOR_WHITE).with_scale(2));
+//! let (width, height) = font.measure("Score");
+//! ```
+
+use crate::{display, utils, Color, Point};
+
+/// Size of the font header: magic number, glyph height and glyph count
+const HEADER_SIZE: usize = 8;
+/// Size of a glyph entry: code point, width, advance and bitmap offset
+const ENTRY_SIZE: usize = 10;
+
+/// Character drawn in place of the characters missing from a font
+const REPLACEMENT_CHARACTER: char = '?';
+
+/// # Font converted at build time (EFF1 format)
+///
+/// Read in place from the binary (usually from flash), nothing is copied in RAM.
+#[derive(Clone, Copy)]
+pub struct Font {
+    data: &'static [u8],
+    height: u16,
+    glyph_count: u16,
+}
+
+/// A glyph of a [`Font`]
+#[derive(Clone, Copy)]
+pub struct Glyph {
+    /// Width of the glyph pixels
+    pub width: u8,
+    /// Horizontal distance to the next glyph (spacing included)
+    pub advance: u8,
+    /// Glyph pixels on 1 bit, row by row, most significant bit first
+    bitmap: &'static [u8],
+}
+
+impl Glyph {
+    /// Returns `true` if the pixel at `(x, y)` of the glyph is set.
+    pub fn pixel(&self, x: u8, y: u16) -> bool {
+        let index = y as usize * self.width as usize + x as usize;
+        self.bitmap.get(index / 8).is_some_and(|byte| byte & (0x80 >> (index % 8)) != 0)
+    }
+}
+
+/// Color, background and scale used to draw text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextStyle {
+    /// Color of the glyphs
+    pub color: Color,
+    /// Background of the glyphs, `None` for a transparent background
+    pub background: Option<Color>,
+    /// Integer scaling factor (`1` for the original size)
+    pub scale: u16,
+}
+
+impl TextStyle {
+    /// Creates a style with a transparent background, at the original size.
+    pub const fn new(color: Color) -> Self {
+        TextStyle { color, background: None, scale: 1 }
+    }
+
+    /// Returns the style with an opaque background.
+    pub const fn with_background(self, background: Color) -> Self {
+        TextStyle { background: Some(background), ..self }
+    }
+
+    /// Returns the style with an integer scaling factor (at least 1).
+    pub const fn with_scale(self, scale: u16) -> Self {
+        TextStyle { scale: if scale == 0 { 1 } else { scale }, ..self }
+    }
+}
+
+impl Font {
+    /// Reads a font from an EFF1 binary (see [`include_font!`](crate::include_font)).
+    ///
+    /// Returns `None` if the magic number is wrong or the data is too short.
+    pub const fn from_raw(data: &'static [u8]) -> Option<Self> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let magic_number = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+        if magic_number != utils::EFF1_MAGIC_NUMBER {
+            return None;
+        }
+
+        let height = u16::from_le_bytes([data[4], data[5]]);
+        let glyph_count = u16::from_le_bytes([data[6], data[7]]);
+        if data.len() < HEADER_SIZE + glyph_count as usize * ENTRY_SIZE {
+            return None;
+        }
+
+        Some(Font { data, height, glyph_count })
+    }
+
+    /// Height of a line of text, in pixels
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the glyph of a character, `None` if the font does not have it.
+    pub fn glyph(&self, character: char) -> Option<Glyph> {
+        let code_point = character as u32;
+
+        // Binary search in the entries, sorted by code point
+        let (mut low, mut high) = (0usize, self.glyph_count as usize);
+        while low < high {
+            let middle = (low + high) / 2;
+            let entry = &self.data[HEADER_SIZE + middle * ENTRY_SIZE..HEADER_SIZE + (middle + 1) * ENTRY_SIZE];
+            let entry_code_point = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+
+            if entry_code_point == code_point {
+                let offset = u32::from_le_bytes([entry[6], entry[7], entry[8], entry[9]]) as usize;
+                let start = HEADER_SIZE + self.glyph_count as usize * ENTRY_SIZE + offset;
+                let length = (entry[4] as usize * self.height as usize).div_ceil(8);
+
+                return Some(Glyph {
+                    width: entry[4],
+                    advance: entry[5],
+                    bitmap: self.data.get(start..start + length).unwrap_or(&[]),
+                });
+            }
+
+            if entry_code_point < code_point {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        None
+    }
+
+    /// Returns the size `(width, height)` of a text drawn at the original size.
+    ///
+    /// Each `\n` starts a new line.
+    pub fn measure(&self, text: &str) -> (u16, u16) {
+        let mut width: u32 = 0;
+        let mut lines: u32 = 1;
+        let mut line_width: u32 = 0;
+
+        for character in text.chars() {
+            if character == '\n' {
+                lines += 1;
+                line_width = 0;
+                continue;
+            }
+            line_width += self.advance(character) as u32;
+            width = width.max(line_width);
+        }
+
+        (width.min(u16::MAX as u32) as u16, (lines * self.height as u32).min(u16::MAX as u32) as u16)
+    }
+
+    /// Draws a text at `point` (top-left corner) with a transparent background.
+    pub fn draw(&self, text: &str, point: Point, color: Color) {
+        self.draw_styled(text, point, &TextStyle::new(color));
+    }
+
+    /// Draws a text at `point` (top-left corner) with a [`TextStyle`].
+    ///
+    /// Only the set pixels are pushed when the background is transparent. Text outside of the screen is clipped.
+    pub fn draw_styled(&self, text: &str, point: Point, style: &TextStyle) {
+        let scale = style.scale.max(1) as i32;
+        let line_height = self.height as i32 * scale;
+        let (mut x, mut y) = (point.x as i32, point.y as i32);
+
+        for character in text.chars() {
+            if character == '\n' {
+                x = point.x as i32;
+                y += line_height;
+                continue;
+            }
+
+            let advance = self.advance(character) as i32 * scale;
+            if let Some(background) = style.background {
+                display::draw::fill_span_rect(x, y, advance, line_height, background);
+            }
+            if let Some(glyph) = self.glyph(character).or_else(|| self.glyph(REPLACEMENT_CHARACTER)) {
+                self.draw_glyph(&glyph, x, y, scale, style.color);
+            }
+
+            x += advance;
+        }
+    }
+
+    /// Pushes the runs of set pixels of each glyph row.
+    fn draw_glyph(&self, glyph: &Glyph, x: i32, y: i32, scale: i32, color: Color) {
+        for row in 0..self.height {
+            let mut column = 0u8;
+            while column < glyph.width {
+                if !glyph.pixel(column, row) {
+                    column += 1;
+                    continue;
+                }
+
+                let start = column;
+                while column < glyph.width && glyph.pixel(column, row) {
+                    column += 1;
+                }
+
+                display::draw::fill_span_rect(
+                    x + start as i32 * scale,
+                    y + row as i32 * scale,
+                    (column - start) as i32 * scale,
+                    scale,
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Advance of a character, replacement glyph included.
+    fn advance(&self, character: char) -> u8 {
+        self.glyph(character)
+            .or_else(|| self.glyph(REPLACEMENT_CHARACTER))
+            .map_or((self.height / 2).min(u8::MAX as u16) as u8, |glyph| glyph.advance)
+    }
+}
@@ -1,6 +1,11 @@
 
 /// Sleep for the specified number of microseconds.
 pub fn usleep(us: u32) {
+    #[cfg(not(target_os = "none"))]
+    if virtual_clock::sleep(us as u64) {
+        return;
+    }
+
     unsafe {
         eadk_timing_usleep(us);
     }
@@ -8,6 +13,11 @@ pub fn usleep(us: u32) {
 
 /// Sleep for the specified number of milliseconds.
 pub fn msleep(ms: u32) {
+    #[cfg(not(target_os = "none"))]
+    if virtual_clock::sleep(ms as u64 * 1000) {
+        return;
+    }
+
     unsafe {
         eadk_timing_msleep(ms);
     }
@@ -17,11 +27,133 @@ pub fn msleep(ms: u32) {
 /// 
 /// Does not take sleep modes into account.
 pub fn millis() -> u64 {
+    #[cfg(not(target_os = "none"))]
+    if let Some(ms) = virtual_clock::millis() {
+        return ms;
+    }
+
     unsafe { eadk_timing_millis() }
 }
 
+#[cfg(not(all(not(target_os = "none"), feature = "host-timing")))]
 unsafe extern "C" {
     fn eadk_timing_usleep(us: u32);
     fn eadk_timing_msleep(ms: u32);
     fn eadk_timing_millis() -> u64;
+}
+
+// Without Epsilon (feature `host-timing`), the virtual clock is always used
+#[cfg(all(not(target_os = "none"), feature = "host-timing"))]
+unsafe fn eadk_timing_usleep(_us: u32) {}
+#[cfg(all(not(target_os = "none"), feature = "host-timing"))]
+unsafe fn eadk_timing_msleep(_ms: u32) {}
+#[cfg(all(not(target_os = "none"), feature = "host-timing"))]
+unsafe fn eadk_timing_millis() -> u64 {
+    virtual_clock::now_us() / 1000
+}
+
+// ==================================================================================
+// ================================= VIRTUAL CLOCK ==================================
+// ==================================================================================
+
+/// Uses a virtual clock on the current thread instead of the system one, starting at `start_ms`.
+///
+/// With the virtual clock, [`millis`] returns simulated time: [`msleep`] and [`usleep`] move it
+/// forward at once instead of waiting, and tests move it with [`advance`] or stop it with [`freeze`].
+/// An installed [`Replay`](crate::input::replay::Replay) follows the virtual clock, so animations
+/// and timeouts run deterministically.
+///
+/// With the `host-timing` feature (enabled by `testing`), the virtual clock is always used and
+/// host binaries link without the Epsilon simulator.
+///
+/// ```rust,ignore
+/// eadkp::timing::use_virtual_clock(0);
+/// show_popup("Saved", 2000);
+/// eadkp::timing::advance(1999);
+/// assert!(popup_visible());
+/// eadkp::timing::advance(1);
+/// assert!(!popup_visible());
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn use_virtual_clock(start_ms: u64) {
+    virtual_clock::update(|clock| *clock = virtual_clock::VirtualClock { enabled: true, frozen: false, now_us: start_ms * 1000 });
+}
+
+/// Goes back to the system clock on the current thread (see [`use_virtual_clock`]).
+#[cfg(not(target_os = "none"))]
+pub fn use_system_clock() {
+    virtual_clock::update(|clock| clock.enabled = false);
+}
+
+/// Returns `true` if [`millis`] returns the time of the virtual clock.
+#[cfg(not(target_os = "none"))]
+pub fn virtual_clock_enabled() -> bool {
+    virtual_clock::millis().is_some()
+}
+
+/// Moves the virtual clock forward by `ms` milliseconds, even when it is frozen.
+#[cfg(not(target_os = "none"))]
+pub fn advance(ms: u64) {
+    virtual_clock::update(|clock| clock.now_us += ms * 1000);
+}
+
+/// Freezes (or unfreezes) the virtual clock: [`msleep`] and [`usleep`] return at once without moving it.
+///
+/// The scans and event waits of an input [`Replay`](crate::input::replay::Replay) don't move it either,
+/// only [`advance`] does.
+#[cfg(not(target_os = "none"))]
+pub fn freeze(frozen: bool) {
+    virtual_clock::update(|clock| clock.frozen = frozen);
+}
+
+/// Waits `ms` milliseconds on the virtual clock like [`msleep`] (nothing while frozen).
+#[cfg(not(target_os = "none"))]
+pub(crate) fn sleep_virtual(ms: u64) {
+    virtual_clock::sleep(ms * 1000);
+}
+
+#[cfg(not(target_os = "none"))]
+mod virtual_clock {
+    use std::cell::Cell;
+
+    #[derive(Clone, Copy)]
+    pub(super) struct VirtualClock {
+        pub enabled: bool,
+        pub frozen: bool,
+        pub now_us: u64,
+    }
+
+    thread_local! {
+        static CLOCK: Cell<VirtualClock> = const {
+            Cell::new(VirtualClock { enabled: cfg!(feature = "host-timing"), frozen: false, now_us: 0 })
+        };
+    }
+
+    pub(super) fn update(f: impl FnOnce(&mut VirtualClock)) {
+        CLOCK.with(|cell| {
+            let mut clock = cell.get();
+            f(&mut clock);
+            cell.set(clock);
+        });
+    }
+
+    #[cfg(feature = "host-timing")]
+    pub(super) fn now_us() -> u64 {
+        CLOCK.with(|cell| cell.get().now_us)
+    }
+
+    /// Time of the virtual clock, `None` if it isn't used
+    pub(super) fn millis() -> Option<u64> {
+        let clock = CLOCK.with(Cell::get);
+        clock.enabled.then_some(clock.now_us / 1000)
+    }
+
+    /// Moves the clock forward by `us` unless frozen. Returns `false` if the virtual clock isn't used.
+    pub(super) fn sleep(us: u64) -> bool {
+        let clock = CLOCK.with(Cell::get);
+        if clock.enabled && !clock.frozen {
+            update(|clock| clock.now_us += us);
+        }
+        clock.enabled
+    }
 }
\ No newline at end of file
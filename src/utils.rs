@@ -2,9 +2,15 @@
 /// Magic number for EIF1 format. Magic number in hex `0x31464945`
 pub const EIF1_MAGIC_NUMBER: u32 = u32::from_le_bytes(*b"EIF1"); 
 
+/// Magic number for EIF2 format. Magic number in hex `0x32464945`
+pub const EIF2_MAGIC_NUMBER: u32 = u32::from_le_bytes(*b"EIF2");
+
+/// Magic number for EFF1 font format. Magic number in hex `0x31464645`
+pub const EFF1_MAGIC_NUMBER: u32 = u32::from_le_bytes(*b"EFF1");
+
 /// Rectangle structure
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub struct Rect {
     pub x: u16,
@@ -54,7 +60,7 @@ pub const LARGE_FONT: FontSize = FontSize {
 
 /// 2D Point structure
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub struct Point {
     pub x: u16,
@@ -0,0 +1,84 @@
+//! Off-screen framebuffer: clears composed into the tiles and dirty-tile flushing
+
+use eadkp::display::{self, FrameBuffer};
+use eadkp::{Color, Rect, COLOR_BLACK, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+
+/// Marks the simulated screen, to see which areas a flush pushed.
+const MARKER: Color = Color { rgb565: 0x07E0 };
+
+fn square(x: u16) -> Rect {
+    Rect { x, y: 10, width: 10, height: 10 }
+}
+
+fn pixel(x: u16, y: u16) -> Color {
+    display::host::pixel(x, y).unwrap()
+}
+
+#[test]
+fn clear_is_composed_into_the_tiles() {
+    display::host::clear(COLOR_WHITE);
+    let mut framebuffer = FrameBuffer::full();
+
+    framebuffer.clear(COLOR_BLACK);
+    framebuffer.fill_rect(square(5), COLOR_RED);
+    framebuffer.flush();
+    assert_eq!(pixel(5, 10), COLOR_RED);
+    assert_eq!(pixel(4, 10), COLOR_BLACK);
+    assert_eq!(pixel(319, 239), COLOR_BLACK);
+
+    // Next frame: the square moves to the second tile
+    display::host::clear(MARKER);
+    framebuffer.clear(COLOR_BLACK);
+    framebuffer.fill_rect(square(40), COLOR_RED);
+    assert!(framebuffer.is_dirty());
+    framebuffer.flush();
+
+    // The tile of the old square is pushed whole, with the clear color over the square
+    assert_eq!(pixel(5, 10), COLOR_BLACK);
+    assert_eq!(pixel(31, 23), COLOR_BLACK);
+    // The new square is drawn over a tile already showing the clear color: only the square is pushed
+    assert_eq!(pixel(40, 10), COLOR_RED);
+    assert_eq!(pixel(32, 0), MARKER);
+    // The other tiles already showed the clear color: no full-screen push
+    assert_eq!(pixel(64, 0), MARKER);
+    assert_eq!(pixel(319, 239), MARKER);
+}
+
+#[test]
+fn clear_with_a_new_color_repaints_every_tile() {
+    display::host::clear(COLOR_WHITE);
+    let mut framebuffer = FrameBuffer::full();
+
+    framebuffer.clear(COLOR_BLACK);
+    framebuffer.flush();
+    assert!(!framebuffer.is_dirty());
+
+    // Same color again: nothing to push
+    framebuffer.clear(COLOR_BLACK);
+    assert!(!framebuffer.is_dirty());
+
+    framebuffer.clear(COLOR_BLUE);
+    framebuffer.fill_rect(square(5), COLOR_RED);
+    framebuffer.flush();
+    assert_eq!(pixel(0, 0), COLOR_BLUE);
+    assert_eq!(pixel(5, 10), COLOR_RED);
+    assert_eq!(pixel(319, 239), COLOR_BLUE);
+}
+
+#[test]
+fn small_budget_keeps_the_pending_clear() {
+    display::host::clear(COLOR_WHITE);
+    let mut framebuffer = FrameBuffer::new(display::TILE_BYTES);
+
+    // Each square needs its own tile: the first one is pushed early and released
+    framebuffer.clear(COLOR_BLACK);
+    framebuffer.fill_rect(square(5), COLOR_RED);
+    framebuffer.fill_rect(square(200), COLOR_BLUE);
+    framebuffer.flush();
+
+    assert_eq!(pixel(5, 10), COLOR_RED);
+    assert_eq!(pixel(0, 0), COLOR_BLACK);
+    assert_eq!(pixel(200, 10), COLOR_BLUE);
+    assert_eq!(pixel(199, 10), COLOR_BLACK);
+    assert_eq!(pixel(319, 239), COLOR_BLACK);
+}
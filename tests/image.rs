@@ -0,0 +1,136 @@
+//! Lecture et dessin des images EIF2
+
+use eadkp::{display, Color, Eif2Image, Image, ImageError, ImageRef, Point, COLOR_WHITE};
+
+/// Compressions, voir `docs/TECHNICALS/EIF/EIF2.md`
+const NONE: u8 = 0;
+const RLE: u8 = 1;
+const LZ: u8 = 2;
+
+/// Image EIF2 RGB565 sans palette ni transparence
+fn eif2(compression: u8, width: u16, height: u16, data: &[u8]) -> Vec<u8> {
+    eif2_with_key(compression, width, height, None, data)
+}
+
+/// Image EIF2 RGB565 sans palette, avec une color key éventuelle
+fn eif2_with_key(compression: u8, width: u16, height: u16, color_key: Option<u16>, data: &[u8]) -> Vec<u8> {
+    let mut raw = b"EIF2".to_vec();
+    raw.extend([1, compression, 16, color_key.is_some() as u8]);
+    raw.extend(width.to_le_bytes());
+    raw.extend(height.to_le_bytes());
+    raw.extend(0u16.to_le_bytes()); // Palette
+    raw.extend(color_key.unwrap_or(0).to_le_bytes());
+    raw.extend((data.len() as u32).to_le_bytes());
+    raw.extend(data);
+    raw
+}
+
+/// Répète la couleur `0xF800` sur 100 pixels (10x10) en une seule répétition RLE
+fn rle_red_square() -> Vec<u8> {
+    eif2(RLE, 10, 10, &[126 + 100, 0x00, 0xF8])
+}
+
+/// 2x2 pixels : une couleur brute (2 octets) puis une référence de 6 octets à distance 2
+fn lz_blue_square() -> Vec<u8> {
+    eif2(LZ, 2, 2, &[0b0000_0011, 0x1F, 0x00, 0x00, 0x13])
+}
+
+fn leak(raw: Vec<u8>) -> &'static [u8] {
+    Box::leak(raw.into_boxed_slice())
+}
+
+#[test]
+fn compressed_streams_decode() {
+    let red = Image::try_from_bytes(&rle_red_square()).unwrap();
+    assert_eq!((red.width, red.height), (10, 10));
+    assert!(red.pixels.iter().all(|pixel| pixel.rgb565 == 0xF800));
+
+    let blue = Image::try_from_bytes(&lz_blue_square()).unwrap();
+    assert_eq!(blue.pixels.len(), 4);
+    assert!(blue.pixels.iter().all(|pixel| pixel.rgb565 == 0x001F));
+}
+
+#[test]
+fn truncated_rle_stream_is_rejected() {
+    // 4 pixels répétés au lieu de 100
+    let raw = eif2(RLE, 10, 10, &[126 + 4, 0x00, 0xF8]);
+
+    let expected = Err(ImageError::DimensionMismatch { expected: 100, found: 4 });
+    assert_eq!(Eif2Image::try_parse(&raw).map(|_| ()), expected);
+    assert_eq!(ImageRef::try_from_bytes(&raw).map(|_| ()), expected);
+    assert_eq!(Image::try_from_bytes(&raw).map(|_| ()), expected);
+}
+
+#[test]
+fn truncated_lz_stream_is_rejected() {
+    // Un seul pixel brut, puis plus rien
+    let raw = eif2(LZ, 10, 10, &[0b0000_0011, 0x1F, 0x00]);
+
+    let expected = Err(ImageError::DimensionMismatch { expected: 100, found: 1 });
+    assert_eq!(Eif2Image::try_parse(&raw).map(|_| ()), expected);
+    assert_eq!(ImageRef::try_from_bytes(&raw).map(|_| ()), expected);
+    assert_eq!(Image::try_from_bytes(&raw).map(|_| ()), expected);
+}
+
+#[test]
+fn image_ref_draws_compressed_streams() {
+    display::host::clear(COLOR_WHITE);
+
+    ImageRef::from_raw(leak(rle_red_square())).unwrap().draw(Point { x: 5, y: 5 });
+    ImageRef::from_raw(leak(lz_blue_square())).unwrap().draw(Point { x: 318, y: 238 });
+
+    assert_eq!(display::host::pixel(5, 5), Some(Color { rgb565: 0xF800 }));
+    assert_eq!(display::host::pixel(14, 14), Some(Color { rgb565: 0xF800 }));
+    assert_eq!(display::host::pixel(15, 15), Some(COLOR_WHITE));
+    assert_eq!(display::host::pixel(319, 239), Some(Color { rgb565: 0x001F }));
+}
+
+#[test]
+fn unsupported_encodings() {
+    let mut raw = rle_red_square();
+    raw[4] = 2;
+    assert_eq!(Eif2Image::try_parse(&raw).map(|_| ()), Err(ImageError::UnsupportedVersion { version: 2 }));
+
+    let mut raw = rle_red_square();
+    raw[5] = 7;
+    assert_eq!(Eif2Image::try_parse(&raw).map(|_| ()), Err(ImageError::UnsupportedCompression { compression: 7 }));
+
+    let mut raw = rle_red_square();
+    raw[6] = 3;
+    assert_eq!(Eif2Image::try_parse(&raw).map(|_| ()), Err(ImageError::UnsupportedBpp { bits_per_pixel: 3 }));
+}
+
+#[test]
+fn oversized_stream_decodes_only_the_declared_pixels() {
+    // Bombe RLE : 60 répétitions de 129 pixels pour une image de 10x10
+    let mut data = Vec::new();
+    for _ in 0..60 {
+        data.extend([255, 0x00, 0xF8]);
+    }
+
+    let image = Image::try_from_bytes(&eif2(RLE, 10, 10, &data)).unwrap();
+    assert_eq!(image.pixels.len(), 100);
+}
+
+#[test]
+fn image_ref_draws_bands_and_transparency() {
+    display::host::clear(COLOR_WHITE);
+
+    // 40x30 pixels verts : plusieurs bandes de lignes, coupées au bord droit de l'écran
+    let mut data = Vec::new();
+    for _ in 0..9 {
+        data.extend([255, 0xE0, 0x07]);
+    }
+    data.extend([126 + 39, 0xE0, 0x07]);
+    let green = ImageRef::from_raw(leak(eif2(RLE, 40, 30, &data))).unwrap();
+    green.draw(Point { x: 300, y: 0 });
+    assert_eq!(display::host::pixel(300, 0), Some(Color { rgb565: 0x07E0 }));
+    assert_eq!(display::host::pixel(319, 29), Some(Color { rgb565: 0x07E0 }));
+    assert_eq!(display::host::pixel(319, 30), Some(COLOR_WHITE));
+
+    // Le second pixel porte la color key : il n'est pas dessiné
+    let keyed = ImageRef::from_raw(leak(eif2_with_key(NONE, 2, 1, Some(0xF81F), &[0x00, 0xF8, 0x1F, 0xF8]))).unwrap();
+    keyed.draw(Point { x: 0, y: 100 });
+    assert_eq!(display::host::pixel(0, 100), Some(Color { rgb565: 0xF800 }));
+    assert_eq!(display::host::pixel(1, 100), Some(COLOR_WHITE));
+}
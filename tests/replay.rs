@@ -0,0 +1,134 @@
+//! Scripted input replay driving `KeyboardState::scan` and `event_get`
+
+use eadkp::input::replay::{self, Replay, ScriptError};
+use eadkp::input::{self, Event, Key, KeyboardState};
+use eadkp::timing;
+
+fn script_error(script: &str) -> ScriptError {
+    Replay::from_script(script).unwrap_err()
+}
+
+#[test]
+fn script_drives_the_keyboard() {
+    timing::use_virtual_clock(0);
+    let script = "
+        # Hold Ok for a bit more than 3 frames
+        t=0 press Ok
+        t=40 press up; t=50 release OK
+        t=64 state 0x0
+    ";
+    input::set_source(Replay::from_script(script).unwrap());
+
+    // Scans at 0, 16, 32, 48 then 64 ms
+    let scans: Vec<KeyboardState> = (0..5).map(|_| KeyboardState::scan()).collect();
+    assert!(scans[0].key_down(Key::Ok) && !scans[0].key_down(Key::Up));
+    assert!(scans[2].key_down(Key::Ok) && !scans[2].key_down(Key::Up));
+    assert!(scans[3].key_down(Key::Ok) && scans[3].key_down(Key::Up));
+    assert_eq!(scans[4], KeyboardState::new());
+
+    assert_eq!(timing::millis(), 80);
+    assert!(input::source_finished());
+    input::clear_source();
+}
+
+#[test]
+fn events_respect_the_timeout() {
+    timing::use_virtual_clock(0);
+    input::set_source(Replay::from_script("t=100 event Ok; t=1000 event back").unwrap());
+
+    // Not due within the timeout: the clock only moves by the timeout
+    assert_eq!(input::try_event_get(50), None);
+    assert_eq!(timing::millis(), 50);
+
+    // Due within the timeout: the clock moves to the event
+    assert_eq!(input::try_event_get(500), Some(Event::Ok));
+    assert_eq!(timing::millis(), 100);
+
+    assert_eq!(input::event_get(5000), Event::Back);
+    assert_eq!(timing::millis(), 1000);
+
+    assert_eq!(input::try_event_get(20), None);
+    assert_eq!(timing::millis(), 1020);
+    input::clear_source();
+}
+
+#[test]
+fn event_get_waits_for_the_next_event() {
+    timing::use_virtual_clock(0);
+    input::set_source(Replay::from_script("t=300 event Ok; t=400 press Back; t=500 release Back").unwrap());
+
+    // A polling loop with a short timeout gets the event instead of panicking
+    assert_eq!(input::event_get(10), Event::Ok);
+    assert_eq!(timing::millis(), 300);
+
+    // No event left: the remaining keyboard changes are played before giving up
+    let result = std::panic::catch_unwind(|| input::event_get(10));
+    assert!(result.is_err());
+    assert_eq!(timing::millis(), 500);
+    assert!(input::source_finished());
+    input::clear_source();
+}
+
+#[test]
+#[should_panic(expected = "frozen")]
+fn event_get_on_a_frozen_clock_panics() {
+    timing::use_virtual_clock(0);
+    input::set_source(Replay::new().event(100, Event::Back));
+    timing::freeze(true);
+    input::event_get(10);
+}
+
+#[test]
+fn advance_moves_the_replay_clock() {
+    // With the virtual clock, then with the own clock of the replay
+    for virtual_clock in [true, false] {
+        if virtual_clock {
+            timing::use_virtual_clock(0);
+        } else {
+            timing::use_system_clock();
+        }
+        input::set_source(Replay::new().with_scan_interval(0).press(30, Key::Back));
+
+        assert!(!KeyboardState::scan().key_down(Key::Back));
+        replay::advance(29);
+        assert!(!KeyboardState::scan().key_down(Key::Back));
+        replay::advance(1);
+        assert!(KeyboardState::scan().key_down(Key::Back));
+        input::clear_source();
+    }
+}
+
+#[test]
+fn frozen_clock_ignores_scans() {
+    timing::use_virtual_clock(0);
+    input::set_source(Replay::new().press(16, Key::Ok).event(100, Event::Back));
+    timing::freeze(true);
+
+    assert!(!KeyboardState::scan().key_down(Key::Ok));
+    assert!(!KeyboardState::scan().key_down(Key::Ok));
+    assert_eq!(input::try_event_get(500), None);
+    assert_eq!(timing::millis(), 0);
+
+    replay::advance(16);
+    assert!(KeyboardState::scan().key_down(Key::Ok));
+    timing::freeze(false);
+    input::clear_source();
+}
+
+#[test]
+fn script_errors() {
+    assert_eq!(script_error("press Ok").reason, "expected `t=<ms>`");
+    assert_eq!(script_error("t=abc press Ok").reason, "invalid time");
+    assert_eq!(script_error("t=10").reason, "missing command");
+    assert_eq!(script_error("t=10 press").reason, "missing argument");
+    assert_eq!(script_error("t=10 press Ok Back").reason, "too many words");
+    assert_eq!(script_error("t=10 press Enter").reason, "unknown key");
+    assert_eq!(script_error("t=10 state 0xZZ").reason, "invalid keyboard state");
+    assert_eq!(script_error("t=10 event Nothing").reason, "unknown event");
+    assert_eq!(script_error("t=10 hold Ok").reason, "unknown command");
+
+    // The error points at the statement, not the whole script
+    let error = script_error("t=0 press Ok; t=10 jump Ok");
+    assert_eq!(error.statement, "t=10 jump Ok");
+    assert_eq!(error.to_string(), "unknown command in `t=10 jump Ok`");
+}
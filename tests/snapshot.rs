@@ -0,0 +1,73 @@
+//! Golden-image snapshots of the host display backend
+
+use std::path::PathBuf;
+use std::sync::Once;
+
+use eadkp::testing::{self, SnapshotError};
+use eadkp::{display, Rect, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+
+/// Uses a snapshot directory inside `target/` so the tests don't write to `tests/snapshots`.
+fn snapshot_dir() -> PathBuf {
+    static INIT: Once = Once::new();
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("snapshots");
+
+    INIT.call_once(|| {
+        let _ = std::fs::remove_dir_all(&directory);
+        std::env::set_var(testing::SNAPSHOT_DIR_VAR, &directory);
+    });
+    directory
+}
+
+fn draw_scene(square: Rect) {
+    testing::reset(COLOR_WHITE);
+    display::push_rect_uniform(square, COLOR_BLUE);
+}
+
+#[test]
+fn snapshot_match() {
+    let directory = snapshot_dir();
+    let square = Rect { x: 10, y: 20, width: 30, height: 40 };
+
+    // The first run writes the reference, the second one compares with it
+    draw_scene(square);
+    testing::capture().check("match").unwrap();
+    assert!(directory.join("match.png").exists());
+
+    draw_scene(square);
+    testing::assert_snapshot("match");
+    assert!(!directory.join("match.diff.png").exists());
+}
+
+#[test]
+fn snapshot_mismatch() {
+    let directory = snapshot_dir();
+
+    draw_scene(Rect { x: 10, y: 20, width: 30, height: 40 });
+    testing::capture().check("mismatch").unwrap();
+
+    // Same scene with 2x3 pixels of another color
+    draw_scene(Rect { x: 10, y: 20, width: 30, height: 40 });
+    display::push_rect_uniform(Rect { x: 100, y: 50, width: 2, height: 3 }, COLOR_RED);
+
+    match testing::capture().check("mismatch") {
+        Err(SnapshotError::Mismatch { differing, bounds, diff }) => {
+            assert_eq!(differing, 6);
+            assert_eq!(bounds, Rect { x: 100, y: 50, width: 2, height: 3 });
+            assert_eq!(diff, directory.join("mismatch.diff.png"));
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+    assert!(directory.join("mismatch.actual.png").exists());
+    assert!(directory.join("mismatch.diff.png").exists());
+}
+
+#[test]
+fn snapshot_size_mismatch() {
+    snapshot_dir();
+
+    draw_scene(Rect { x: 0, y: 0, width: 8, height: 8 });
+    testing::capture_rect(Rect { x: 0, y: 0, width: 16, height: 16 }).check("size").unwrap();
+
+    let error = testing::capture_rect(Rect { x: 0, y: 0, width: 16, height: 8 }).check("size").unwrap_err();
+    assert!(matches!(error, SnapshotError::SizeMismatch { expected: (16, 16), found: (16, 8) }));
+}
@@ -0,0 +1,170 @@
+//! Stockage émulé sur l'hôte : lecture/écriture, sauvegardes typées et réparation de la chaîne
+
+use eadkp::storage::{self, RecordProblemKind, Storage, StorageError};
+
+/// Sauvegarde de test : niveau, score et nom du joueur
+type Save = (u8, u32, String);
+
+fn sample_save() -> Save {
+    (3, 1200, "Tom".to_string())
+}
+
+/// Stockage émulé du thread courant, magic number compris
+fn raw_storage() -> &'static mut [u8] {
+    let userland = eadkp::system::userland();
+    unsafe { std::slice::from_raw_parts_mut(userland.storage_address as *mut u8, userland.storage_size) }
+}
+
+/// Position de l'enregistrement `filename` (champ taille) dans le stockage émulé
+fn record_offset(filename: &str) -> usize {
+    let name = [filename.as_bytes(), b"\0"].concat();
+    let storage = raw_storage();
+    storage.windows(name.len()).position(|window| window == name).expect("record not found") - 2
+}
+
+// ============================================================================
+// FICHIERS
+// ============================================================================
+
+#[test]
+fn write_read_replace_erase() {
+    storage::host_reset();
+
+    storage::write("score.txt", b"100").unwrap();
+    assert!(storage::file_exists("score.txt"));
+    assert_eq!(storage::read("score.txt").unwrap(), b"100");
+
+    // Remplacer par un contenu plus long, puis plus court
+    storage::write("score.txt", b"123456").unwrap();
+    assert_eq!(storage::read("score.txt").unwrap(), b"123456");
+    storage::write("score.txt", b"7").unwrap();
+    assert_eq!(storage::read("score.txt").unwrap(), b"7");
+    assert_eq!(storage::stats().unwrap().record_count, 1);
+
+    Storage::take().unwrap().erase("score.txt").unwrap();
+    assert!(!storage::file_exists("score.txt"));
+    assert_eq!(storage::read("score.txt"), Err(StorageError::FileNotFound));
+}
+
+#[test]
+fn missing_file() {
+    storage::host_reset();
+
+    assert_eq!(storage::read("missing.txt"), Err(StorageError::FileNotFound));
+    assert_eq!(Storage::take().unwrap().erase("missing.txt"), Err(StorageError::FileNotFound));
+    assert_eq!(storage::rename("missing.txt", "other.txt"), Err(StorageError::FileNotFound));
+}
+
+#[test]
+fn full_storage() {
+    storage::host_reset();
+
+    let too_big = vec![0xAA; storage::HOST_STORAGE_SIZE];
+    assert!(matches!(storage::write("big.bin", &too_big), Err(StorageError::StorageOverflow { .. })));
+    assert!(!storage::file_exists("big.bin"));
+
+    // Remplir presque tout le stockage : le remplacement a besoin de la place des deux versions
+    let free = storage::stats().unwrap().free;
+    storage::write("big.bin", &vec![0xAA; free - 64]).unwrap();
+    assert!(matches!(storage::write("big.bin", &[0xBB; 128]), Err(StorageError::StorageOverflow { .. })));
+    assert_eq!(storage::read("big.bin").unwrap().len(), free - 64);
+}
+
+// ============================================================================
+// SAUVEGARDES
+// ============================================================================
+
+#[test]
+fn save_and_load() {
+    storage::host_reset();
+
+    storage::save("game.sav", &sample_save()).unwrap();
+    assert_eq!(storage::load::<Save>("game.sav").unwrap(), sample_save());
+
+    storage::save_versioned("game2.sav", 4, &sample_save()).unwrap();
+    assert_eq!(storage::load_versioned::<Save>("game2.sav", 4).unwrap(), sample_save());
+}
+
+#[test]
+fn load_version_mismatch() {
+    storage::host_reset();
+
+    storage::save_versioned("game.sav", 1, &sample_save()).unwrap();
+    assert_eq!(
+        storage::load_versioned::<Save>("game.sav", 2),
+        Err(StorageError::VersionMismatch { expected: 2, found: 1 })
+    );
+}
+
+#[test]
+fn load_checksum_mismatch() {
+    storage::host_reset();
+
+    storage::save("game.sav", &sample_save()).unwrap();
+
+    // Corrompre le dernier octet des données (après le nom et l'en-tête de sauvegarde)
+    let mut content = storage::read("game.sav").unwrap();
+    *content.last_mut().unwrap() ^= 0xFF;
+    storage::write("game.sav", &content).unwrap();
+
+    assert!(matches!(storage::load::<Save>("game.sav"), Err(StorageError::ChecksumMismatch { .. })));
+}
+
+// ============================================================================
+// VERIFICATION
+// ============================================================================
+
+#[test]
+fn verify_and_repair_broken_chain() {
+    storage::host_reset();
+
+    storage::write("a.txt", b"first").unwrap();
+    storage::write("b.txt", b"second").unwrap();
+    storage::write("c.txt", b"third").unwrap();
+    assert!(storage::verify().unwrap().is_ok());
+
+    // Taille de `b.txt` trop petite pour un en-tête : la chaîne est cassée à cet enregistrement
+    let offset = record_offset("b.txt");
+    raw_storage()[offset..offset + 2].copy_from_slice(&1u16.to_ne_bytes());
+
+    let report = storage::verify().unwrap();
+    assert_eq!(report.records, 1);
+    assert_eq!(report.problems.len(), 1);
+    assert_eq!(report.problems[0].offset, offset);
+    assert_eq!(report.problems[0].kind, RecordProblemKind::InvalidSize { size: 1 });
+    assert!(report.problems[0].kind.breaks_chain());
+
+    assert_eq!(storage::repair().unwrap(), report);
+    assert!(storage::verify().unwrap().is_ok());
+    assert_eq!(storage::read("a.txt").unwrap(), b"first");
+    assert!(!storage::file_exists("b.txt"));
+    assert!(!storage::file_exists("c.txt"));
+
+    // L'espace tronqué est de nouveau libre
+    storage::write("b.txt", b"again").unwrap();
+    assert_eq!(storage::read("b.txt").unwrap(), b"again");
+}
+
+#[test]
+fn repair_keeps_most_recent_duplicate() {
+    storage::host_reset();
+
+    // Coupure pendant `file_replace` : la nouvelle version est écrite, l'ancienne pas encore retirée
+    storage::write("save.txt", b"old").unwrap();
+    storage::write("other.txt", b"other").unwrap();
+    storage::write("savf.txt", b"new").unwrap();
+    let offset = record_offset("savf.txt");
+    raw_storage()[offset + 2..offset + 6].copy_from_slice(b"save");
+
+    let report = storage::verify().unwrap();
+    assert_eq!(report.records, 3);
+    assert_eq!(report.problems.len(), 1);
+    assert_eq!(report.problems[0].offset, record_offset("save.txt"));
+    assert_eq!(report.problems[0].kind, RecordProblemKind::DuplicateName);
+    assert_eq!(storage::read("save.txt").unwrap(), b"old");
+
+    storage::repair().unwrap();
+    assert!(storage::verify().unwrap().is_ok());
+    assert_eq!(storage::read("save.txt").unwrap(), b"new");
+    assert_eq!(storage::read("other.txt").unwrap(), b"other");
+}
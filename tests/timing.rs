@@ -0,0 +1,63 @@
+//! Virtual clock of the host builds
+
+use eadkp::timing;
+
+#[test]
+fn sleeps_move_the_virtual_clock() {
+    timing::use_virtual_clock(1000);
+    assert!(timing::virtual_clock_enabled());
+    assert_eq!(timing::millis(), 1000);
+
+    timing::msleep(250);
+    assert_eq!(timing::millis(), 1250);
+
+    // Microseconds add up
+    for _ in 0..4 {
+        timing::usleep(500);
+    }
+    assert_eq!(timing::millis(), 1252);
+}
+
+#[test]
+fn advance_moves_the_virtual_clock() {
+    timing::use_virtual_clock(0);
+
+    timing::advance(1999);
+    assert_eq!(timing::millis(), 1999);
+    timing::advance(1);
+    assert_eq!(timing::millis(), 2000);
+}
+
+#[test]
+fn freeze_stops_sleeps_only() {
+    timing::use_virtual_clock(0);
+    timing::freeze(true);
+
+    timing::msleep(100);
+    timing::usleep(100);
+    assert_eq!(timing::millis(), 0);
+
+    // Tests can still move a frozen clock
+    timing::advance(40);
+    assert_eq!(timing::millis(), 40);
+
+    timing::freeze(false);
+    timing::msleep(10);
+    assert_eq!(timing::millis(), 50);
+}
+
+#[test]
+fn clock_is_per_thread() {
+    timing::use_virtual_clock(0);
+    timing::advance(500);
+
+    std::thread::spawn(|| {
+        timing::use_virtual_clock(0);
+        timing::msleep(20);
+        assert_eq!(timing::millis(), 20);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(timing::millis(), 500);
+}